@@ -2,7 +2,7 @@
 
 use crc::{Crc, CRC_64_XZ};
 use criterion::*;
-use rand::{thread_rng, RngCore};
+use rand::{thread_rng, Rng, RngCore};
 
 const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
 
@@ -53,5 +53,94 @@ fn bench_crc(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_crc);
+fn bench_checksum_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 batch");
+    let mut rng = thread_rng();
+
+    // 1024 messages each, of the same length, of random lengths, or with one
+    // long message in every 8.
+    let cases: [(&str, Vec<usize>); 5] = [
+        ("256", vec![256; 1024]),
+        ("1024", vec![1024; 1024]),
+        ("4096", vec![4096; 1024]),
+        (
+            "mixed",
+            (0..1024).map(|_| rng.gen_range(64..=4096)).collect(),
+        ),
+        (
+            "skewed",
+            (0..1024)
+                .map(|i| if i % 8 == 0 { 65536 } else { 64 })
+                .collect(),
+        ),
+    ];
+
+    for (name, lengths) in &cases {
+        let total = lengths.iter().sum::<usize>();
+        let mut buf = vec![0u8; total];
+        rng.fill_bytes(&mut buf);
+        let mut inputs = Vec::with_capacity(lengths.len());
+        let mut rest = &buf[..];
+        for len in lengths {
+            let (input, tail) = rest.split_at(*len);
+            inputs.push(input);
+            rest = tail;
+        }
+        let mut out = vec![0; inputs.len()];
+
+        group.throughput(Throughput::Bytes(total as u64));
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast_nvme::simd", name),
+            &inputs,
+            |b, inputs| {
+                b.iter(|| {
+                    for (input, crc) in inputs.iter().zip(out.iter_mut()) {
+                        let mut digest = crc64fast_nvme::Digest::new();
+                        digest.write(input);
+                        *crc = digest.sum64();
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast_nvme::checksum_batch", name),
+            &inputs,
+            |b, inputs| b.iter(|| crc64fast_nvme::checksum_batch(inputs, &mut out)),
+        );
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn bench_par_checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 parallel");
+    let mut rng = thread_rng();
+
+    for &size in &[24, 28] {
+        let mut buf = vec![0u8; 1 << size];
+        rng.fill_bytes(&mut buf);
+
+        group.throughput(Throughput::Bytes(1 << size));
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast_nvme::simd", size),
+            &buf,
+            |b, buf| {
+                b.iter(|| {
+                    let mut digest = crc64fast_nvme::Digest::new();
+                    digest.write(buf);
+                    digest.sum64()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast_nvme::par_checksum", size),
+            &buf,
+            |b, buf| b.iter(|| crc64fast_nvme::par_checksum(buf)),
+        );
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, bench_crc, bench_checksum_batch);
+#[cfg(feature = "rayon")]
+criterion_group!(benches, bench_crc, bench_checksum_batch, bench_par_checksum);
 criterion_main!(benches);
@@ -0,0 +1,9 @@
+#![no_std]
+
+use crc64fast_nvme::Digest;
+
+pub fn checksum(bytes: &[u8]) -> u64 {
+    let mut digest = Digest::new();
+    digest.write(bytes);
+    digest.sum64()
+}
@@ -4,6 +4,9 @@
 #include <ostream>
 #include <new>
 
+/// The length of a serialized [`Digest`] state, in bytes.
+constexpr static const uintptr_t DIGEST_STATE_LEN = 20;
+
 /// Represents an in-progress CRC-64 computation.
 struct Digest;
 
@@ -31,6 +34,25 @@ void digest_write(DigestHandle *handle, const char *data, uintptr_t len);
 /// Uses unsafe method calls
 uint64_t digest_sum64(const DigestHandle *handle);
 
+/// Exports the Digest state into `out` (C-compatible shared library)
+///
+/// Writes `DIGEST_STATE_LEN` bytes and returns that length, or returns 0 if
+/// `len` is too small.
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+uintptr_t digest_export(const DigestHandle *handle, char *out, uintptr_t len);
+
+/// Creates a new Digest from an exported state (C-compatible shared library)
+///
+/// Returns null if the data is not an exported Digest state.
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+DigestHandle *digest_import(const char *data, uintptr_t len);
+
 /// Frees the Digest (C-compatible shared library)
 ///
 /// # Safety
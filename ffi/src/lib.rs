@@ -0,0 +1,296 @@
+//! C-compatible shared library for
+//! [`crc64fast-nvme`](https://crates.io/crates/crc64fast-nvme).
+//!
+//! It is built as a separate crate, so that the Rust library itself stays a
+//! plain `lib` which can be linked into `no_std` binaries. `cargo build -p
+//! crc64fast-nvme-ffi` produces the shared library, whose functions are
+//! declared in `crc64fast_nvme.h`.
+//!
+//! ### Example (PHP)
+//!
+//! ```php
+//! $digest = $ffi->digest_new();
+//! $ffi->digest_write($digest, 'hello world!', 12);
+//! $checksum = $ffi->digest_sum64($digest); // 0xd9160d1fa8e418e3
+//! ```
+
+use core::ffi::c_char;
+use core::ptr;
+use core::slice;
+
+use crc64::{Digest, DIGEST_STATE_LEN};
+
+/// Opaque type for C for use in FFI (C-compatible shared library)
+#[repr(C)]
+pub struct DigestHandle(*mut Digest);
+
+/// Creates a new Digest (C-compatible shared library)
+#[no_mangle]
+pub extern "C" fn digest_new() -> *mut DigestHandle {
+    let digest = Box::new(Digest::new());
+    let handle = Box::new(DigestHandle(Box::into_raw(digest)));
+    Box::into_raw(handle)
+}
+
+/// Writes data to the Digest (C-compatible shared library)
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+#[no_mangle]
+pub unsafe extern "C" fn digest_write(handle: *mut DigestHandle, data: *const c_char, len: usize) {
+    if handle.is_null() || data.is_null() {
+        return;
+    }
+
+    let digest = &mut *(*handle).0;
+    let bytes = slice::from_raw_parts(data as *const u8, len);
+    digest.write(bytes);
+}
+
+/// Calculates the CRC-64 checksum from the Digest (C-compatible shared library)
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+#[no_mangle]
+pub unsafe extern "C" fn digest_sum64(handle: *const DigestHandle) -> u64 {
+    if handle.is_null() {
+        return 0;
+    }
+
+    let digest = &*(*handle).0;
+    digest.sum64()
+}
+
+/// Exports the Digest state into `out` (C-compatible shared library)
+///
+/// Writes `DIGEST_STATE_LEN` bytes and returns that length, or returns 0 if
+/// `len` is too small.
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+#[no_mangle]
+pub unsafe extern "C" fn digest_export(
+    handle: *const DigestHandle,
+    out: *mut c_char,
+    len: usize,
+) -> usize {
+    if handle.is_null() || out.is_null() || len < DIGEST_STATE_LEN {
+        return 0;
+    }
+
+    let digest = &*(*handle).0;
+    let out = slice::from_raw_parts_mut(out as *mut u8, DIGEST_STATE_LEN);
+    out.copy_from_slice(&digest.to_bytes());
+    DIGEST_STATE_LEN
+}
+
+/// Creates a new Digest from an exported state (C-compatible shared library)
+///
+/// Returns null if the data is not an exported Digest state.
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+#[no_mangle]
+pub unsafe extern "C" fn digest_import(data: *const c_char, len: usize) -> *mut DigestHandle {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+
+    let bytes = slice::from_raw_parts(data as *const u8, len);
+    match Digest::from_bytes(bytes) {
+        Some(digest) => {
+            let handle = Box::new(DigestHandle(Box::into_raw(Box::new(digest))));
+            Box::into_raw(handle)
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees the Digest (C-compatible shared library)
+///
+/// # Safety
+///
+/// Uses unsafe method calls
+#[no_mangle]
+pub unsafe extern "C" fn digest_free(handle: *mut DigestHandle) {
+    if !handle.is_null() {
+        let handle = Box::from_raw(handle);
+        let _ = Box::from_raw(handle.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_digest_lifecycle() {
+        unsafe {
+            // Create new digest
+            let handle = digest_new();
+            assert!(!handle.is_null(), "Digest creation failed");
+
+            // Write some data
+            let data = b"hello world!";
+            digest_write(handle, data.as_ptr() as *const c_char, data.len());
+
+            // Get sum and verify against known value
+            let sum = digest_sum64(handle);
+            assert_eq!(sum, 0xd9160d1fa8e418e3, "CRC64 calculation incorrect");
+
+            // Clean up
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_null_handling() {
+        unsafe {
+            // Test null handle with write
+            digest_write(ptr::null_mut(), b"test".as_ptr() as *const c_char, 4);
+
+            // Test null data with valid handle
+            let handle = digest_new();
+            digest_write(handle, ptr::null(), 0);
+
+            // Test null handle with sum64
+            let sum = digest_sum64(ptr::null());
+            assert_eq!(sum, 0, "Null handle should return 0");
+
+            // Clean up
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_empty_data() {
+        unsafe {
+            let handle = digest_new();
+
+            // Write empty data
+            digest_write(handle, b"".as_ptr() as *const c_char, 0);
+            let sum = digest_sum64(handle);
+            assert_eq!(sum, 0, "Empty data should produce 0");
+
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_binary_data() {
+        unsafe {
+            let handle = digest_new();
+
+            // Test with binary data including null bytes
+            let data = [0u8, 1, 2, 3, 0, 4, 5, 0, 6];
+            digest_write(handle, data.as_ptr() as *const c_char, data.len());
+
+            // Write additional data to test streaming
+            let more_data = [7u8, 8, 9];
+            digest_write(handle, more_data.as_ptr() as *const c_char, more_data.len());
+
+            let sum = digest_sum64(handle);
+            assert_ne!(sum, 0, "Binary data should produce non-zero CRC");
+
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_large_vectors() {
+        unsafe {
+            let zeros = vec![0u8; 4096];
+            let ones = vec![255u8; 4096];
+
+            let handle = digest_new();
+            digest_write(handle, zeros.as_ptr() as *const c_char, zeros.len());
+            let sum = digest_sum64(handle);
+            assert_eq!(sum, 0x6482d367eb22b64e, "Failed on 4096 zeros");
+            digest_free(handle);
+
+            let handle = digest_new();
+            digest_write(handle, ones.as_ptr() as *const c_char, ones.len());
+            let sum = digest_sum64(handle);
+            assert_eq!(sum, 0xc0ddba7302eca3ac, "Failed on 4096 ones");
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_standard_strings() {
+        unsafe {
+            let test_cases: Vec<(&[u8], u64)> = vec![(b"123456789", 0xae8b14860a799888), (b"", 0)];
+
+            for (input, expected) in test_cases {
+                let handle = digest_new();
+                digest_write(handle, input.as_ptr() as *const c_char, input.len());
+                let sum = digest_sum64(handle);
+                assert_eq!(sum, expected, "Failed on test vector: {:?}", input);
+                digest_free(handle);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ffi_export_import() {
+        unsafe {
+            let handle = digest_new();
+            let data = b"hello ";
+            digest_write(handle, data.as_ptr() as *const c_char, data.len());
+
+            let mut state = [0u8; DIGEST_STATE_LEN];
+            let len = digest_export(handle, state.as_mut_ptr() as *mut c_char, state.len());
+            assert_eq!(len, DIGEST_STATE_LEN, "Export failed");
+            digest_free(handle);
+
+            let handle = digest_import(state.as_ptr() as *const c_char, len);
+            assert!(!handle.is_null(), "Import failed");
+            let data = b"world!";
+            digest_write(handle, data.as_ptr() as *const c_char, data.len());
+            assert_eq!(
+                digest_sum64(handle),
+                0xd9160d1fa8e418e3,
+                "Resumed CRC incorrect"
+            );
+            digest_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_export_import_invalid() {
+        unsafe {
+            let handle = digest_new();
+            let mut state = [0u8; DIGEST_STATE_LEN];
+            let out = state.as_mut_ptr() as *mut c_char;
+            assert_eq!(digest_export(ptr::null(), out, state.len()), 0);
+            assert_eq!(digest_export(handle, ptr::null_mut(), state.len()), 0);
+            assert_eq!(digest_export(handle, out, state.len() - 1), 0);
+            digest_free(handle);
+
+            assert!(digest_import(ptr::null(), 0).is_null());
+            assert!(digest_import(state.as_ptr() as *const c_char, state.len()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_ffi_incremental_update() {
+        unsafe {
+            let handle = digest_new();
+
+            // Write data incrementally
+            let data = "hello world!";
+            for byte in data.bytes() {
+                digest_write(handle, &byte as *const u8 as *const c_char, 1);
+            }
+
+            let sum = digest_sum64(handle);
+            assert_eq!(sum, 0xd9160d1fa8e418e3, "Incremental update failed");
+
+            digest_free(handle);
+        }
+    }
+}
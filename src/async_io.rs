@@ -0,0 +1,241 @@
+//! Checksumming data as it flows through Tokio's [`AsyncRead`] and
+//! [`AsyncWrite`] streams.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::Digest;
+
+pin_project! {
+    /// An async reader which computes the CRC-64/NVME of the data read
+    /// through it.
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::AsyncCrcReader;
+    /// use tokio::io::AsyncReadExt;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut reader = AsyncCrcReader::new(&b"hello world!"[..]);
+    /// let mut data = Vec::new();
+    /// reader.read_to_end(&mut data).await.unwrap();
+    /// assert_eq!(reader.sum64(), 0xd9160d1fa8e418e3);
+    /// # });
+    /// ```
+    pub struct AsyncCrcReader<R> {
+        #[pin]
+        inner: R,
+        digest: Digest,
+    }
+}
+
+impl<R> AsyncCrcReader<R> {
+    /// Wraps `inner`, starting a new checksum.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+        }
+    }
+
+    /// Returns the CRC-64/NVME of the data read so far.
+    pub fn sum64(&self) -> u64 {
+        self.digest.sum64()
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn amount(&self) -> u64 {
+        self.digest.amount
+    }
+
+    /// Returns the digest of the data read so far.
+    pub fn digest(&self) -> &Digest {
+        &self.digest
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Data read directly from it is not checksummed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for AsyncCrcReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        let result = this.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            this.digest.write(&buf.filled()[before..]);
+        }
+        result
+    }
+}
+
+pin_project! {
+    /// An async writer which computes the CRC-64/NVME of the data written
+    /// through it.
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::AsyncCrcWriter;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let mut writer = AsyncCrcWriter::new(Vec::new());
+    /// writer.write_all(b"hello world!").await.unwrap();
+    /// assert_eq!(writer.sum64(), 0xd9160d1fa8e418e3);
+    /// # });
+    /// ```
+    pub struct AsyncCrcWriter<W> {
+        #[pin]
+        inner: W,
+        digest: Digest,
+    }
+}
+
+impl<W> AsyncCrcWriter<W> {
+    /// Wraps `inner`, starting a new checksum.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+        }
+    }
+
+    /// Returns the CRC-64/NVME of the data written so far.
+    pub fn sum64(&self) -> u64 {
+        self.digest.sum64()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn amount(&self) -> u64 {
+        self.digest.amount
+    }
+
+    /// Returns the digest of the data written so far.
+    pub fn digest(&self) -> &Digest {
+        &self.digest
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Data written directly to it is not checksummed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for AsyncCrcWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let result = this.inner.poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = result {
+            this.digest.write(&buf[..n]);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{checksum, runtime};
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    /// Sends `bytes` through a duplex stream with a `max_buf_size` buffer, in
+    /// chunks of `chunk` bytes, and returns both sides once done.
+    async fn send(
+        bytes: &[u8],
+        max_buf_size: usize,
+        chunk: usize,
+    ) -> (
+        AsyncCrcWriter<tokio::io::DuplexStream>,
+        AsyncCrcReader<tokio::io::DuplexStream>,
+        Vec<u8>,
+    ) {
+        let (client, server) = duplex(max_buf_size);
+        let mut writer = AsyncCrcWriter::new(client);
+        let mut reader = AsyncCrcReader::new(server);
+        let mut received = Vec::new();
+        let write = async {
+            for part in bytes.chunks(chunk) {
+                writer.write_all(part).await.unwrap();
+            }
+            writer.shutdown().await.unwrap();
+        };
+        let read = async {
+            reader.read_to_end(&mut received).await.unwrap();
+        };
+        tokio::join!(write, read);
+        (writer, reader, received)
+    }
+
+    #[test]
+    fn test_duplex() {
+        runtime().block_on(async {
+            let (writer, reader, received) = send(b"hello world!", 5, 3).await;
+            assert_eq!(received, b"hello world!");
+            assert_eq!(writer.sum64(), 0xd9160d1fa8e418e3);
+            assert_eq!(reader.sum64(), 0xd9160d1fa8e418e3);
+            assert_eq!(writer.amount(), 12);
+            assert_eq!(reader.amount(), 12);
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn duplex_round_trip(
+            bytes in any_with::<Vec<u8>>(size_range(..65536).lift()),
+            max_buf_size in 1..4096usize,
+            chunk in 1..8192usize,
+        ) {
+            let (writer, reader, received) = runtime().block_on(send(&bytes, max_buf_size, chunk));
+            prop_assert_eq!(writer.sum64(), checksum(&bytes));
+            prop_assert_eq!(reader.sum64(), checksum(&bytes));
+            prop_assert_eq!(reader.amount(), bytes.len() as u64);
+            prop_assert_eq!(received, bytes);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Checksumming many small, independent messages at once.
+
+use super::pclmulqdq;
+
+/// Computes the CRC-64/NVME of each of `inputs` into the same position of
+/// `out`.
+///
+/// With SIMD support, groups of messages are folded together, so that the
+/// carryless multiplications of one do not wait on the results of the
+/// previous ones. This is faster than checksumming short messages one by
+/// one, especially when they have similar lengths; messages of 2 KiB or more
+/// gain nothing from it.
+///
+/// # Panics
+///
+/// Panics if `inputs` and `out` have different lengths.
+///
+/// ```rust
+/// use crc64fast_nvme::checksum_batch;
+///
+/// let mut out = [0; 2];
+/// checksum_batch(&[&b"hello world!"[..], &[]], &mut out);
+/// assert_eq!(out, [0xd9160d1fa8e418e3, 0]);
+/// ```
+pub fn checksum_batch(inputs: &[&[u8]], out: &mut [u64]) {
+    assert_eq!(
+        inputs.len(),
+        out.len(),
+        "inputs and out must have the same length"
+    );
+    let computer = pclmulqdq::get_update();
+    out.fill(!0);
+    unsafe { (computer.update_many)(out, inputs) };
+    for crc in out {
+        *crc = !*crc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::{size_range, vec};
+    use proptest::prelude::*;
+
+    fn check(inputs: &[Vec<u8>]) -> Result<(), TestCaseError> {
+        let inputs: Vec<&[u8]> = inputs.iter().map(Vec::as_slice).collect();
+        let mut out = vec![0; inputs.len()];
+        checksum_batch(&inputs, &mut out);
+        for (bytes, crc) in inputs.iter().zip(&out) {
+            prop_assert_eq!(*crc, checksum(bytes));
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_length_mismatch() {
+        checksum_batch(&[b"hello world!"], &mut [0; 2]);
+    }
+
+    proptest! {
+        #[test]
+        fn equivalent_to_digest(inputs in vec(any_with::<Vec<u8>>(size_range(..4096).lift()), ..40)) {
+            check(&inputs)?;
+        }
+
+        #[test]
+        fn equivalent_to_digest_similar_lengths(
+            inputs in (16..4096usize).prop_flat_map(|len| vec(vec(any::<u8>(), len..len + 64), 1..40)),
+        ) {
+            check(&inputs)?;
+        }
+    }
+}
@@ -15,22 +15,93 @@ const POLY: u64 = 0xAD93D23594C93659;
 //  ./build_table 1    # generate TABLE_1
 //
 // etc.
+//
+//  ./build_table slice-by 8  # generate TABLE_0 to TABLE_7 (for the `slice-by-8` feature)
+//
+//  ./build_table x2n      # generate X2N_TABLE
+//  ./build_table x2n-inv  # generate X2N_INV_TABLE
+//  ./build_table rev      # generate TABLE_REV
 
 fn long_div_step(m: u64) -> u64 {
     m << 1 ^ if m >> 63 != 0 { POLY } else { 0 }
 }
 
-fn main() {
-    let table_id = args().nth(1).unwrap().parse::<u32>().unwrap();
-    println!("static TABLE_{}: [u64; 256] = [", table_id);
+/// Computes `a * b mod POLY` (non-reflected).
+fn mul_mod(a: u64, b: u64) -> u64 {
+    let mut value = 0;
+    for i in (0..64).rev() {
+        value = long_div_step(value);
+        if a >> i & 1 != 0 {
+            value ^= b;
+        }
+    }
+    value
+}
+
+fn crc_table_entry(table_id: u32, i: u8) -> u64 {
     let count = table_id * 8 + 8;
+    let byte = i.reverse_bits();
+    let mut value = u64::from(byte) << 56;
+    for _ in 0..count {
+        value = long_div_step(value);
+    }
+    value.reverse_bits()
+}
+
+fn build_crc_table(table_id: u32) {
+    println!("static TABLE_{}: [u64; 256] = [", table_id);
     for i in 0..=255u8 {
-        let byte = i.reverse_bits();
-        let mut value = u64::from(byte) << 56;
-        for _ in 0..count {
-            value = long_div_step(value);
-        }
+        println!("    {:#018x},", crc_table_entry(table_id, i));
+    }
+    println!("];");
+}
+
+fn build_crc_tables(count: u32) {
+    for table_id in 0..count {
+        build_crc_table(table_id);
+        println!();
+    }
+}
+
+fn build_reverse_table() {
+    // The top byte of `TABLE_0[i]` is unique for every `i`, so the index can
+    // be recovered from it. Each entry holds `TABLE_0[i] << 8 | i`.
+    let mut table = [None; 256];
+    for i in 0..=255u8 {
+        let value = crc_table_entry(0, i);
+        let slot = &mut table[(value >> 56) as usize];
+        assert!(slot.is_none(), "top bytes of TABLE_0 are not unique");
+        *slot = Some(value << 8 | u64::from(i));
+    }
+    println!("static TABLE_REV: [u64; 256] = [");
+    for value in table {
+        println!("    {:#018x},", value.unwrap());
+    }
+    println!("];");
+}
+
+fn build_x2n_table(name: &str, x8: u64) {
+    println!("static {}: [u64; 64] = [", name);
+    let mut value = x8;
+    for _ in 0..64 {
         println!("    {:#018x},", value.reverse_bits());
+        value = mul_mod(value, value);
     }
     println!("];");
 }
+
+fn main() {
+    match args().nth(1).unwrap().as_str() {
+        "rev" => build_reverse_table(),
+        "slice-by" => build_crc_tables(args().nth(2).unwrap().parse().unwrap()),
+        "x2n" => build_x2n_table("X2N_TABLE", 1 << 8),
+        "x2n-inv" => {
+            // x⁻¹ = (POLY + x⁶⁴ - 1) / x, since POLY has a constant term.
+            let x_inv = POLY >> 1 | 1 << 63;
+            let x_inv_2 = mul_mod(x_inv, x_inv);
+            let x_inv_4 = mul_mod(x_inv_2, x_inv_2);
+            build_x2n_table("X2N_INV_TABLE", mul_mod(x_inv_4, x_inv_4))
+        }
+        table_id => build_crc_table(table_id.parse().unwrap()),
+    }
+}
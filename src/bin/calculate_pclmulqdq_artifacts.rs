@@ -74,7 +74,7 @@ fn bit_reverse(mut forward: u64) -> u64 {
 /// # Parameters
 ///
 /// * `polynomial`: The polynomial for which the multiplicative inverse is to be calculated.
-///                 This is typically the CRC polynomial.
+///   This is typically the CRC polynomial.
 ///
 /// # Returns
 ///
@@ -133,8 +133,8 @@ fn generate_mu(polynomial: u64) -> u64 {
 /// # Parameters
 ///
 /// * `exponent`: The exponent value, representing the degree to which the polynomial is raised.
-///               If `exponent` is less than or equal to 64, the function returns 0, as the
-///               operation does not produce a meaningful result in such cases.
+///   If `exponent` is less than or equal to 64, the function returns 0, as the
+///   operation does not produce a meaningful result in such cases.
 /// * `polynomial`: The polynomial used for the key generation. This is typically a CRC polynomial.
 ///
 /// # Returns
@@ -290,7 +290,7 @@ mod bit_reverse_tests {
         let expected: u64 = 0x9a6c9329ac4bc9b5;
         let output: u64 = bit_reverse(input);
 
-        println!("Expected {}", format!("{output:#x}"));
+        println!("Expected {output:#x}");
 
         assert_eq!(bit_reverse(input), expected);
     }
@@ -1,4 +1,4 @@
-use crc64fast_nvme::Digest;
+use crc64fast_nvme::{forge, forge_insert, Digest};
 /// Generates CRC-64/NVME checksums, using SIMD-accelerated
 /// carryless-multiplication, from a file on disk.
 use std::env;
@@ -13,7 +13,7 @@ const CRC_NVME: crc::Algorithm<u64> = crc::Algorithm {
     refout: true,
     xorout: 0xFFFFFFFFFFFFFFFF,
     check: 0xae8b14860a799888,
-    residue: 0x0000000000000000,
+    residue: 0xf310303b2b6f6e42,
 };
 
 fn calculate_crc_64_simd_from_file(file: &str) -> u64 {
@@ -52,6 +52,33 @@ fn calculate_crc_64_validate_from_string(input: &str) -> u64 {
     digest.finalize()
 }
 
+fn parse_u64(input: &str) -> Option<u64> {
+    match input.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
+    }
+}
+
+fn forge_from_file(file: &str, offset: &str, target: &str, insert: bool) -> Option<String> {
+    let data = std::fs::read(file).unwrap();
+    let offset = usize::try_from(parse_u64(offset)?).ok()?;
+    let target = parse_u64(target)?;
+
+    let patch = if insert {
+        if offset > data.len() {
+            return None;
+        }
+        forge_insert(&data, offset, target)
+    } else {
+        if offset.checked_add(8)? > data.len() {
+            return None;
+        }
+        forge(&data, offset, target)
+    };
+
+    Some(patch.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
 fn main() -> ExitCode {
     let args: Vec<String> = env::args().collect();
 
@@ -60,6 +87,11 @@ fn main() -> ExitCode {
         println!("Example for a file: crc_64_nvm_checksum --file /path/to/file");
         println!("Example for a string: crc_64_nvm_checksum --string 123456789");
         println!("Optionally including '--validate-slow' in the argument list will skip SIMD calculation, typically just for testing.");
+        println!();
+        println!("Usage: crc_64_nvm_checksum --forge [file] [offset] [targetChecksum] [--insert]");
+        println!("Example: crc_64_nvm_checksum --forge /path/to/file 16 0xae8b14860a799888");
+        println!("Prints the 8 bytes (as hex) which, written over the file at the offset, make its checksum the target.");
+        println!("Optionally including '--insert' will compute bytes to insert instead.");
 
         return ExitCode::from(1);
     }
@@ -88,6 +120,32 @@ fn main() -> ExitCode {
         }
     }
 
+    if "--forge" == input_type && (args.len() == 5 || args.len() == 6) {
+        let file = &args[2];
+
+        if fs::metadata(file).is_err() {
+            println!("Couldn't open file {}", file);
+
+            return ExitCode::from(1);
+        }
+
+        let insert = match args.get(5).map(String::as_str) {
+            None => false,
+            Some("--insert") => true,
+            Some(_) => {
+                println!("An error occurred, likely due to bad command-line arguments.");
+
+                return ExitCode::from(1);
+            }
+        };
+
+        if let Some(patch) = forge_from_file(file, &args[3], &args[4], insert) {
+            println!("{}", patch);
+
+            return ExitCode::from(0);
+        }
+    }
+
     if "--string" == input_type {
         let input = &args[2];
 
@@ -0,0 +1,263 @@
+//! Sending the CRC-64/NVME of an HTTP body in its trailers.
+//!
+//! S3-compatible stores accept the checksum of a streamed upload as the
+//! `x-amz-checksum-crc64nvme` trailer (announced with the `x-amz-trailer`
+//! header, and for HTTP/1.1 also the `trailer` header), so the body does not
+//! have to be read twice.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+
+use super::Digest;
+
+/// The name of the trailer holding the checksum, as used by S3.
+pub const CHECKSUM_TRAILER: &str = "x-amz-checksum-crc64nvme";
+
+pin_project! {
+    /// A body which computes the CRC-64/NVME of the data of another body, and
+    /// sends it in a trailer when the body ends.
+    ///
+    /// The checksum is sent as the base64 encoding of its big-endian bytes,
+    /// in the [`CHECKSUM_TRAILER`] trailer. It is merged into the trailers of
+    /// the inner body, if there are any.
+    ///
+    /// The body never reports an exact size, even if the inner body does: an
+    /// HTTP/1 body sent with a `content-length` cannot carry trailers, so the
+    /// chunked transfer encoding must be used instead.
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::{ChecksumBody, CHECKSUM_TRAILER};
+    /// use http_body::Body;
+    /// use http_body_util::{BodyExt, Full};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let body = ChecksumBody::new(Full::new(&b"hello world!"[..]));
+    /// assert_eq!(body.size_hint().exact(), None);
+    /// let collected = body.collect().await.unwrap();
+    /// assert_eq!(collected.trailers().unwrap()[CHECKSUM_TRAILER], "2RYNH6jkGOM=");
+    /// # });
+    /// ```
+    pub struct ChecksumBody<B> {
+        #[pin]
+        inner: B,
+        digest: Digest,
+        done: bool,
+    }
+}
+
+impl<B> ChecksumBody<B> {
+    /// Wraps `inner`, starting a new checksum.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+            done: false,
+        }
+    }
+
+    /// Returns the CRC-64/NVME of the data sent so far.
+    pub fn sum64(&self) -> u64 {
+        self.digest.sum64()
+    }
+
+    /// Returns a reference to the underlying body.
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Unwraps the underlying body.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+fn add_checksum(trailers: &mut HeaderMap, digest: &Digest) {
+    let value = HeaderValue::from_str(&digest.finalize().to_base64())
+        .expect("base64 is a valid header value");
+    trailers.insert(HeaderName::from_static(CHECKSUM_TRAILER), value);
+}
+
+impl<B> Body for ChecksumBody<B>
+where
+    B: Body,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, B::Error>>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        let frame = match this.inner.poll_frame(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+            Poll::Ready(Some(Ok(frame))) => frame,
+            Poll::Ready(None) => {
+                *this.done = true;
+                let mut trailers = HeaderMap::new();
+                add_checksum(&mut trailers, this.digest);
+                return Poll::Ready(Some(Ok(Frame::trailers(trailers))));
+            }
+        };
+        let frame = match frame.into_data() {
+            Ok(mut data) => {
+                let bytes = data.copy_to_bytes(data.remaining());
+                this.digest.write(&bytes);
+                Frame::data(bytes)
+            }
+            Err(frame) => match frame.into_trailers() {
+                Ok(mut trailers) => {
+                    // No data may follow the trailers.
+                    *this.done = true;
+                    add_checksum(&mut trailers, this.digest);
+                    Frame::trailers(trailers)
+                }
+                Err(_) => return Poll::Ready(None),
+            },
+        };
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // Only keep the lower bound, so that HTTP/1 uses the chunked transfer
+        // encoding, which is the only one carrying trailers.
+        let mut hint = SizeHint::new();
+        hint.set_lower(self.inner.size_hint().lower());
+        hint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{checksum, runtime};
+    use crate::Crc64Nvme;
+    use http_body_util::{BodyExt, Full};
+    use hyper::{server::conn::http1, service::service_fn, Request, Response};
+    use hyper_util::rt::TokioIo;
+    use std::convert::Infallible;
+    use tokio::net::TcpListener;
+
+    /// A body sending each part as a separate data frame.
+    struct Chunked(Vec<&'static [u8]>);
+
+    impl Body for Chunked {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+            if self.0.is_empty() {
+                return Poll::Ready(None);
+            }
+            let part = self.0.remove(0);
+            Poll::Ready(Some(Ok(Frame::data(Bytes::from_static(part)))))
+        }
+    }
+
+    #[test]
+    fn test_trailers_merged() {
+        runtime().block_on(async {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("x-other", HeaderValue::from_static("kept"));
+            let inner = Full::new(Bytes::from_static(b"hello world!"))
+                .with_trailers(async { Some(Ok(trailers)) });
+            let collected = ChecksumBody::new(inner).collect().await.unwrap();
+            let trailers = collected.trailers().unwrap().clone();
+            assert_eq!(trailers["x-other"], "kept");
+            assert_eq!(trailers[CHECKSUM_TRAILER], "2RYNH6jkGOM=");
+            assert_eq!(collected.to_bytes(), &b"hello world!"[..]);
+        });
+    }
+
+    /// Uploads `body` to an in-process server, which checks it against its
+    /// checksum trailer, and returns the response status.
+    async fn upload<B>(body: ChecksumBody<B>) -> u16
+    where
+        B: Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = service_fn(|request: Request<hyper::body::Incoming>| async move {
+                let collected = request.into_body().collect().await.unwrap();
+                let valid = match collected.trailers() {
+                    Some(trailers) => {
+                        let trailer = trailers[CHECKSUM_TRAILER]
+                            .to_str()
+                            .unwrap()
+                            .parse::<Crc64Nvme>()
+                            .unwrap();
+                        trailer.get() == checksum(&collected.to_bytes())
+                    }
+                    None => false,
+                };
+                let status = if valid { 200 } else { 400 };
+                Ok::<_, Infallible>(
+                    Response::builder()
+                        .status(status)
+                        .body(Full::new(Bytes::new()))
+                        .unwrap(),
+                )
+            });
+            http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+                .unwrap();
+        });
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+
+        let request = Request::put("/bucket/object")
+            .header("x-amz-trailer", CHECKSUM_TRAILER)
+            .header("trailer", CHECKSUM_TRAILER)
+            .body(body)
+            .unwrap();
+        let response = sender.send_request(request).await.unwrap();
+        drop(sender);
+        server.await.unwrap();
+        response.status().as_u16()
+    }
+
+    #[test]
+    fn test_upload_to_server() {
+        runtime().block_on(async {
+            let body = ChecksumBody::new(Chunked(vec![b"hello ", b"world", b"!"]));
+            assert_eq!(upload(body).await, 200);
+        });
+    }
+
+    #[test]
+    fn test_upload_full_body_to_server() {
+        runtime().block_on(async {
+            // A body of known length must still be sent with chunked encoding.
+            let body = ChecksumBody::new(Full::new(Bytes::from_static(b"hello world!")));
+            assert_eq!(body.size_hint().lower(), 12);
+            assert_eq!(body.size_hint().exact(), None);
+            assert_eq!(upload(body).await, 200);
+        });
+    }
+}
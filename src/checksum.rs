@@ -0,0 +1,336 @@
+//! A typed CRC-64/NVME value.
+//!
+//! Checksums are exchanged in several forms: as plain integers, as
+//! zero-padded hex strings, as big-endian bytes (e.g. NVMe protection
+//! information), as little-endian trailers, as base64 of the big-endian
+//! bytes (e.g. the `x-amz-checksum-crc64nvme` header), and as decimal
+//! integers. [`Crc64Nvme`] converts between all of them so the byte order only
+//! has to be right once.
+
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use super::Digest;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A CRC-64/NVME checksum value.
+///
+/// It displays as 16 lowercase hex digits, and parses from hex (with an
+/// optional `0x` prefix) or from the base64 form. As unprefixed digits are
+/// always read as hex, the decimal form has its own
+/// [`to_decimal`](Crc64Nvme::to_decimal) and
+/// [`from_decimal`](Crc64Nvme::from_decimal).
+///
+/// ```rust
+/// use crc64fast_nvme::{Crc64Nvme, Digest};
+///
+/// let mut c = Digest::new();
+/// c.write(b"hello world!");
+/// let checksum = c.finalize();
+///
+/// assert_eq!(checksum.to_string(), "d9160d1fa8e418e3");
+/// assert_eq!(checksum.to_base64(), "2RYNH6jkGOM=");
+/// assert_eq!(checksum.get(), 0xd9160d1fa8e418e3);
+/// assert_eq!("2RYNH6jkGOM=".parse::<Crc64Nvme>(), Ok(checksum));
+/// assert_eq!("0xd9160d1fa8e418e3".parse::<Crc64Nvme>(), Ok(checksum));
+///
+/// assert_eq!(checksum.to_decimal(), "15642704785346664675");
+/// assert_eq!(Crc64Nvme::from_decimal("15642704785346664675"), Ok(checksum));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Crc64Nvme(u64);
+
+impl Crc64Nvme {
+    /// Wraps a raw CRC-64/NVME value.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw CRC-64/NVME value.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the checksum as big-endian bytes.
+    pub const fn to_be_bytes(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    /// Returns the checksum as little-endian bytes.
+    pub const fn to_le_bytes(self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    /// Creates a checksum from big-endian bytes.
+    pub const fn from_be_bytes(bytes: [u8; 8]) -> Self {
+        Self(u64::from_be_bytes(bytes))
+    }
+
+    /// Creates a checksum from little-endian bytes.
+    pub const fn from_le_bytes(bytes: [u8; 8]) -> Self {
+        Self(u64::from_le_bytes(bytes))
+    }
+
+    /// Returns the base64 encoding of the big-endian bytes, as used by the
+    /// `x-amz-checksum-crc64nvme` header.
+    #[cfg(feature = "std")]
+    pub fn to_base64(self) -> String {
+        let bytes = self.to_be_bytes();
+        let mut encoded = String::with_capacity(12);
+        for chunk in bytes.chunks(3) {
+            let mut group = [0; 3];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let group = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+            for i in 0..=chunk.len() {
+                let index = group >> (18 - 6 * i) & 0x3f;
+                encoded.push(char::from(BASE64_ALPHABET[index as usize]));
+            }
+        }
+        encoded.push('=');
+        encoded
+    }
+
+    /// Parses the base64 encoding of the big-endian bytes, as used by the
+    /// `x-amz-checksum-crc64nvme` header.
+    pub fn from_base64(s: &str) -> Result<Self, ParseCrc64NvmeError> {
+        let digits = s.strip_suffix('=').ok_or(ParseCrc64NvmeError(()))?;
+        if digits.len() != 11 {
+            return Err(ParseCrc64NvmeError(()));
+        }
+        let mut value: u128 = 0;
+        for c in digits.bytes() {
+            let digit = BASE64_ALPHABET
+                .iter()
+                .position(|a| *a == c)
+                .ok_or(ParseCrc64NvmeError(()))?;
+            value = value << 6 | digit as u128;
+        }
+        // 11 digits carry 66 bits, the last two of which must be zero.
+        if value & 0b11 != 0 {
+            return Err(ParseCrc64NvmeError(()));
+        }
+        Ok(Self((value >> 2) as u64))
+    }
+
+    /// Returns the checksum as a decimal integer.
+    #[cfg(feature = "std")]
+    pub fn to_decimal(self) -> String {
+        self.0.to_string()
+    }
+
+    /// Parses the checksum from a decimal integer of up to 20 digits.
+    pub fn from_decimal(s: &str) -> Result<Self, ParseCrc64NvmeError> {
+        if s.is_empty() || s.len() > 20 || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseCrc64NvmeError(()));
+        }
+        s.parse().map(Self).map_err(|_| ParseCrc64NvmeError(()))
+    }
+
+    fn from_hex(s: &str) -> Result<Self, ParseCrc64NvmeError> {
+        let digits = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if digits.is_empty() || digits.len() > 16 || !digits.bytes().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(ParseCrc64NvmeError(()));
+        }
+        u64::from_str_radix(digits, 16)
+            .map(Self)
+            .map_err(|_| ParseCrc64NvmeError(()))
+    }
+}
+
+impl From<u64> for Crc64Nvme {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Crc64Nvme> for u64 {
+    fn from(checksum: Crc64Nvme) -> Self {
+        checksum.0
+    }
+}
+
+impl fmt::Display for Crc64Nvme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl fmt::LowerHex for Crc64Nvme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for Crc64Nvme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for Crc64Nvme {
+    type Err = ParseCrc64NvmeError;
+
+    /// Parses a checksum from up to 16 hex digits (with an optional `0x`
+    /// prefix), or from its 12-character base64 form. Decimal integers are
+    /// not accepted, since they would be read as hex; use
+    /// [`Crc64Nvme::from_decimal`] instead.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 12 && s.ends_with('=') {
+            Self::from_base64(s)
+        } else {
+            Self::from_hex(s)
+        }
+    }
+}
+
+/// The error returned when parsing a [`Crc64Nvme`] fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCrc64NvmeError(());
+
+impl fmt::Display for ParseCrc64NvmeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid CRC-64/NVME checksum")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseCrc64NvmeError {}
+
+impl Digest {
+    /// Computes the current CRC-64/NVME value, as a [`Crc64Nvme`].
+    pub fn finalize(&self) -> Crc64Nvme {
+        Crc64Nvme(self.sum64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_formatting() {
+        let checksum = Crc64Nvme::new(0x0a79_9888);
+        assert_eq!(checksum.to_string(), "000000000a799888");
+        assert_eq!(format!("{:x}", checksum), "a799888");
+        assert_eq!(format!("{:#018X}", checksum), "0x000000000A799888");
+        assert_eq!(u64::from(checksum).to_string(), "175741064");
+    }
+
+    #[test]
+    fn test_byte_order() {
+        let checksum = Crc64Nvme::new(0xae8b14860a799888);
+        assert_eq!(
+            checksum.to_be_bytes(),
+            [0xae, 0x8b, 0x14, 0x86, 0x0a, 0x79, 0x98, 0x88]
+        );
+        assert_eq!(
+            checksum.to_le_bytes(),
+            [0x88, 0x98, 0x79, 0x0a, 0x86, 0x14, 0x8b, 0xae]
+        );
+        assert_eq!(Crc64Nvme::from_be_bytes(checksum.to_be_bytes()), checksum);
+        assert_eq!(Crc64Nvme::from_le_bytes(checksum.to_le_bytes()), checksum);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_base64() {
+        static CASES: &[(u64, &str)] = &[
+            (0xd9160d1fa8e418e3, "2RYNH6jkGOM="),
+            (0xae8b14860a799888, "rosUhgp5mIg="),
+            (0, "AAAAAAAAAAA="),
+            (u64::MAX, "//////////8="),
+        ];
+        for (value, encoded) in CASES {
+            let checksum = Crc64Nvme::new(*value);
+            assert_eq!(checksum.to_base64(), *encoded);
+            assert_eq!(Crc64Nvme::from_base64(encoded), Ok(checksum));
+            assert_eq!(encoded.parse(), Ok(checksum));
+        }
+    }
+
+    #[test]
+    fn test_decimal() {
+        static CASES: &[(u64, &str)] = &[
+            (0xd9160d1fa8e418e3, "15642704785346664675"),
+            (0x0a79_9888, "175741064"),
+            (0, "0"),
+            (u64::MAX, "18446744073709551615"),
+        ];
+        for (value, decimal) in CASES {
+            let checksum = Crc64Nvme::new(*value);
+            #[cfg(feature = "std")]
+            assert_eq!(checksum.to_decimal(), *decimal);
+            assert_eq!(Crc64Nvme::from_decimal(decimal), Ok(checksum));
+        }
+        // leading zeros are fine, as long as there are at most 20 digits
+        assert_eq!(
+            Crc64Nvme::from_decimal("00000000000175741064"),
+            Ok(Crc64Nvme::new(0x0a79_9888))
+        );
+        // the same digits parse as hex
+        assert_eq!("175741064".parse(), Ok(Crc64Nvme::new(0x1_7574_1064)));
+
+        for input in [
+            "",
+            "+1",
+            "-1",
+            "0x1",
+            "a799888",
+            "18446744073709551616",
+            "000000000000000000001",
+        ] {
+            assert!(Crc64Nvme::from_decimal(input).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "",
+            "0x",
+            "12345678901234567",
+            "xyz",
+            "+1",
+            "//////////9=",
+            "/////////8=",
+            "///////////8=",
+            "//////////8",
+            "////////.//8=",
+        ] {
+            assert!(input.parse::<Crc64Nvme>().is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!("a799888".parse(), Ok(Crc64Nvme::new(0x0a79_9888)));
+        assert_eq!(
+            "0XAE8B14860A799888".parse(),
+            Ok(Crc64Nvme::new(0xae8b14860a799888))
+        );
+        // 12 hex digits are not mistaken for base64
+        assert_eq!("0123456789ab".parse(), Ok(Crc64Nvme::new(0x0123456789ab)));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(value in any::<u64>()) {
+            let checksum = Crc64Nvme::new(value);
+            prop_assert_eq!(checksum.to_string().parse(), Ok(checksum));
+            prop_assert_eq!(format!("{:#x}", checksum).parse(), Ok(checksum));
+            #[cfg(feature = "std")]
+            prop_assert_eq!(checksum.to_base64().parse(), Ok(checksum));
+            #[cfg(feature = "std")]
+            prop_assert_eq!(Crc64Nvme::from_decimal(&checksum.to_decimal()), Ok(checksum));
+        }
+    }
+}
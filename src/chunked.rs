@@ -0,0 +1,255 @@
+//! Checksumming an object from chunks which arrive in any order.
+//!
+//! Each chunk is checksummed on its own, and adjacent chunks are merged with
+//! [`combine`], so only one CRC per contiguous range is kept. Once the ranges
+//! cover the whole object, a single one is left, holding its CRC-64/NVME.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use super::{combine::combine, Digest};
+
+/// Computes the CRC-64/NVME of an object of known length from chunks
+/// written at arbitrary offsets, e.g. by concurrent range downloads.
+///
+/// ```rust
+/// use crc64fast_nvme::ChunkedDigest;
+///
+/// let mut c = ChunkedDigest::new(12);
+/// c.write_at(6, b"world!").unwrap();
+/// assert_eq!(c.sum64(), None);
+/// assert_eq!(c.missing(), [0..6]);
+///
+/// c.write_at(0, b"hello ").unwrap();
+/// assert_eq!(c.sum64(), Some(0xd9160d1fa8e418e3));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkedDigest {
+    len: u64,
+    // start => (end, CRC of the range), for disjoint, non-adjacent ranges.
+    ranges: BTreeMap<u64, (u64, u64)>,
+}
+
+/// The error returned when a chunk cannot be added to a [`ChunkedDigest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkError {
+    /// The chunk extends past the end of the object.
+    OutOfBounds,
+    /// The chunk overlaps a chunk which was already written.
+    Overlap,
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OutOfBounds => "chunk extends past the end of the object",
+            Self::Overlap => "chunk overlaps a chunk already written",
+        })
+    }
+}
+
+impl Error for ChunkError {}
+
+impl ChunkedDigest {
+    /// Creates a new `ChunkedDigest` for an object of `len` bytes.
+    pub fn new(len: u64) -> Self {
+        Self {
+            len,
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the length of the object.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns whether the object is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the chunk `bytes`, found at `offset` in the object.
+    pub fn write_at(&mut self, offset: u64, bytes: &[u8]) -> Result<(), ChunkError> {
+        let mut digest = Digest::new();
+        digest.write(bytes);
+        self.write_crc_at(offset, bytes.len() as u64, digest.sum64())
+    }
+
+    /// Adds a chunk of `len` bytes at `offset` in the object, given only its
+    /// CRC-64/NVME, e.g. when it was checksummed while being streamed to disk.
+    pub fn write_crc_at(&mut self, offset: u64, len: u64, crc: u64) -> Result<(), ChunkError> {
+        let end = offset
+            .checked_add(len)
+            .filter(|end| *end <= self.len)
+            .ok_or(ChunkError::OutOfBounds)?;
+        if len == 0 {
+            return Ok(());
+        }
+        if let Some((_, (prev_end, _))) = self.ranges.range(..=offset).next_back() {
+            if *prev_end > offset {
+                return Err(ChunkError::Overlap);
+            }
+        }
+        if let Some((next_start, _)) = self.ranges.range(offset..).next() {
+            if *next_start < end {
+                return Err(ChunkError::Overlap);
+            }
+        }
+
+        let (mut start, mut end, mut crc) = (offset, end, crc);
+        if let Some((&prev_start, &(prev_end, prev_crc))) = self.ranges.range(..start).next_back() {
+            if prev_end == start {
+                self.ranges.remove(&prev_start);
+                crc = combine(prev_crc, crc, end - start);
+                start = prev_start;
+            }
+        }
+        if let Some((next_end, next_crc)) = self.ranges.remove(&end) {
+            crc = combine(crc, next_crc, next_end - end);
+            end = next_end;
+        }
+        self.ranges.insert(start, (end, crc));
+        Ok(())
+    }
+
+    /// Returns whether every byte of the object has been written.
+    pub fn is_complete(&self) -> bool {
+        self.sum64().is_some()
+    }
+
+    /// Returns the CRC-64/NVME of the object, or `None` if some ranges have
+    /// not been written yet (see [`missing`](Self::missing)).
+    pub fn sum64(&self) -> Option<u64> {
+        if self.len == 0 {
+            return Some(0);
+        }
+        match self.ranges.get(&0) {
+            Some((end, crc)) if *end == self.len => Some(*crc),
+            _ => None,
+        }
+    }
+
+    /// Returns the ranges of the object which have not been written yet, in
+    /// ascending order.
+    pub fn missing(&self) -> Vec<Range<u64>> {
+        let mut missing = Vec::new();
+        let mut covered = 0;
+        for (start, (end, _)) in &self.ranges {
+            if *start > covered {
+                missing.push(covered..*start);
+            }
+            covered = *end;
+        }
+        if covered < self.len {
+            missing.push(covered..self.len);
+        }
+        missing
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_gaps() {
+        let data = b"0123456789abcdef";
+        let mut c = ChunkedDigest::new(16);
+        assert_eq!(c.missing(), [0..16]);
+
+        c.write_at(4, &data[4..6]).unwrap();
+        c.write_at(10, &data[10..12]).unwrap();
+        assert_eq!(c.missing(), [0..4, 6..10, 12..16]);
+        assert!(!c.is_complete());
+        assert_eq!(c.sum64(), None);
+
+        c.write_at(6, &data[6..10]).unwrap();
+        assert_eq!(c.missing(), [0..4, 12..16]);
+        c.write_at(12, &data[12..]).unwrap();
+        c.write_at(0, &data[..4]).unwrap();
+        assert!(c.missing().is_empty());
+        assert!(c.is_complete());
+        assert_eq!(c.sum64(), Some(checksum(data)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut c = ChunkedDigest::new(16);
+        c.write_at(4, b"4567").unwrap();
+        assert_eq!(c.write_at(14, b"efg"), Err(ChunkError::OutOfBounds));
+        assert_eq!(c.write_at(u64::MAX, b"x"), Err(ChunkError::OutOfBounds));
+        assert_eq!(c.write_at(2, b"234"), Err(ChunkError::Overlap));
+        assert_eq!(c.write_at(7, b"78"), Err(ChunkError::Overlap));
+        assert_eq!(c.write_at(0, b"0123456789"), Err(ChunkError::Overlap));
+        assert_eq!(c.write_at(4, b"4"), Err(ChunkError::Overlap));
+        // failed writes leave the state unchanged
+        assert_eq!(c.missing(), [0..4, 8..16]);
+        // empty chunks are accepted anywhere in bounds
+        c.write_at(5, b"").unwrap();
+        c.write_at(16, b"").unwrap();
+    }
+
+    #[test]
+    fn test_empty_object() {
+        let c = ChunkedDigest::new(0);
+        assert!(c.is_complete());
+        assert!(c.missing().is_empty());
+        assert_eq!(c.sum64(), Some(checksum(b"")));
+    }
+
+    fn bytes_and_chunks() -> impl Strategy<Value = (Vec<u8>, Vec<Range<usize>>)> {
+        any_with::<Vec<u8>>(size_range(..16384).lift()).prop_flat_map(|bytes| {
+            let len = bytes.len();
+            (
+                Just(bytes),
+                proptest::collection::vec(0..=len, ..32).prop_map(move |mut cuts| {
+                    cuts.push(0);
+                    cuts.push(len);
+                    cuts.sort_unstable();
+                    cuts.windows(2).map(|w| w[0]..w[1]).collect()
+                }),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn any_order((bytes, chunks) in bytes_and_chunks(), order in any::<prop::sample::Index>(), skip in any::<prop::sample::Index>()) {
+            let mut chunks = chunks;
+            let len = chunks.len();
+            chunks.rotate_left(order.index(len));
+            // interleave from both ends
+            let mut shuffled = Vec::new();
+            while !chunks.is_empty() {
+                shuffled.push(chunks.remove(0));
+                if let Some(chunk) = chunks.pop() {
+                    shuffled.push(chunk);
+                }
+            }
+
+            let mut c = ChunkedDigest::new(bytes.len() as u64);
+            let skipped = skip.index(len);
+            for (i, chunk) in shuffled.iter().enumerate() {
+                if i != skipped {
+                    c.write_at(chunk.start as u64, &bytes[chunk.clone()]).unwrap();
+                }
+            }
+            let gap = &shuffled[skipped];
+            if gap.is_empty() {
+                prop_assert_eq!(c.sum64(), Some(checksum(&bytes)));
+            } else {
+                prop_assert_eq!(c.sum64(), None);
+                prop_assert_eq!(c.missing(), [gap.start as u64..gap.end as u64]);
+                c.write_at(gap.start as u64, &bytes[gap.clone()]).unwrap();
+                prop_assert_eq!(c.sum64(), Some(checksum(&bytes)));
+            }
+        }
+    }
+}
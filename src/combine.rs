@@ -0,0 +1,208 @@
+//! Combining the CRC-64/NVME values of adjacent byte sequences.
+//!
+//! Feeding `n` zero bytes into the (reflected) CRC register is the same as
+//! multiplying its contents by x⁸ⁿ modulo the NVME polynomial, so the CRC of
+//! `A ‖ B` can be derived from `crc(A)`, `crc(B)` and `len(B)` alone, in
+//! O(log(len(B))) GF(2) polynomial multiplications.
+//!
+//! Since the polynomial has a non-zero constant term, x is invertible modulo
+//! it, and the same relation can be solved for `crc(A)` or `crc(B)` instead.
+//!
+//! Polynomials are kept in the same reflected form as the CRC register, i.e.
+//! bit 63 holds the coefficient of x⁰ and bit 0 the coefficient of x⁶³.
+
+use super::table;
+
+/// x⁰ (the multiplicative identity) in reflected form.
+const ONE: u64 = 1 << 63;
+
+/// Multiplies `a` by `b` modulo the NVME polynomial.
+pub(crate) fn multiply(mut a: u64, mut b: u64) -> u64 {
+    let mut product = 0;
+    while a != 0 {
+        if a & ONE != 0 {
+            product ^= b;
+        }
+        a <<= 1;
+        // b = b * x mod POLY
+        b = (b >> 1) ^ (table::POLY_REFLECTED & 0u64.wrapping_sub(b & 1));
+    }
+    product
+}
+
+/// Computes x⁸ⁿ modulo the NVME polynomial, i.e. the factor which shifts a
+/// CRC register past `n` bytes.
+pub(crate) fn x8n(mut n: u64) -> u64 {
+    let mut result = ONE;
+    let mut k = 0;
+    while n != 0 {
+        if n & 1 != 0 {
+            result = multiply(table::X2N_TABLE[k], result);
+        }
+        n >>= 1;
+        k += 1;
+    }
+    result
+}
+
+/// Computes x⁻⁸ⁿ modulo the NVME polynomial, the inverse of [`x8n`].
+pub(crate) fn x8n_inverse(mut n: u64) -> u64 {
+    let mut result = ONE;
+    let mut k = 0;
+    while n != 0 {
+        if n & 1 != 0 {
+            result = multiply(table::X2N_INV_TABLE[k], result);
+        }
+        n >>= 1;
+        k += 1;
+    }
+    result
+}
+
+/// Shifts a CRC register (or CRC value) past `len` bytes of zeros.
+pub(crate) fn shift(crc: u64, len: u64) -> u64 {
+    multiply(x8n(len), crc)
+}
+
+/// Reverts [`shift`], moving a CRC register back over `len` bytes of zeros.
+pub(crate) fn unshift(crc: u64, len: u64) -> u64 {
+    multiply(x8n_inverse(len), crc)
+}
+
+/// Computes the CRC-64/NVME of the concatenation `A ‖ B`, given
+/// `crc_a = crc(A)`, `crc_b = crc(B)` and `len_b = len(B)`.
+///
+/// ```rust
+/// use crc64fast_nvme::{combine, Digest};
+///
+/// let mut a = Digest::new();
+/// a.write(b"hello ");
+/// let mut b = Digest::new();
+/// b.write(b"world!");
+/// assert_eq!(combine(a.sum64(), b.sum64(), 6), 0xd9160d1fa8e418e3);
+/// ```
+pub fn combine(crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    // The initial value and final XOR are both all ones, so their
+    // contributions cancel out and only `crc_a` needs to be shifted.
+    shift(crc_a, len_b) ^ crc_b
+}
+
+/// Computes the CRC-64/NVME of `B`, given `crc_whole = crc(A ‖ B)`,
+/// `crc_prefix = crc(A)` and `len_suffix = len(B)`.
+///
+/// ```rust
+/// use crc64fast_nvme::{crc_of_suffix, Digest};
+///
+/// let mut whole = Digest::new();
+/// whole.write(b"hello world!");
+/// let mut prefix = Digest::new();
+/// prefix.write(b"hello ");
+///
+/// let mut suffix = Digest::new();
+/// suffix.write(b"world!");
+/// assert_eq!(crc_of_suffix(whole.sum64(), prefix.sum64(), 6), suffix.sum64());
+/// ```
+pub fn crc_of_suffix(crc_whole: u64, crc_prefix: u64, len_suffix: u64) -> u64 {
+    crc_whole ^ shift(crc_prefix, len_suffix)
+}
+
+/// Computes the CRC-64/NVME of `A`, given `crc_whole = crc(A ‖ B)`,
+/// `crc_suffix = crc(B)` and `len_suffix = len(B)`.
+///
+/// ```rust
+/// use crc64fast_nvme::{crc_of_prefix, Digest};
+///
+/// let mut whole = Digest::new();
+/// whole.write(b"hello world!");
+/// let mut suffix = Digest::new();
+/// suffix.write(b"world!");
+///
+/// let mut prefix = Digest::new();
+/// prefix.write(b"hello ");
+/// assert_eq!(crc_of_prefix(whole.sum64(), suffix.sum64(), 6), prefix.sum64());
+/// ```
+pub fn crc_of_prefix(crc_whole: u64, crc_suffix: u64, len_suffix: u64) -> u64 {
+    unshift(crc_whole ^ crc_suffix, len_suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{bytes_and_split_index, checksum};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_multiply() {
+        assert_eq!(multiply(ONE, 0x0123_4567_89ab_cdef), 0x0123_4567_89ab_cdef);
+        assert_eq!(multiply(0x0123_4567_89ab_cdef, ONE), 0x0123_4567_89ab_cdef);
+        assert_eq!(multiply(0, 0x0123_4567_89ab_cdef), 0);
+        // x⁶³ · x = x⁶⁴ ≡ POLY − x⁶⁴
+        assert_eq!(multiply(1, ONE >> 1), table::POLY_REFLECTED);
+    }
+
+    #[test]
+    fn test_x8n() {
+        assert_eq!(x8n(0), ONE);
+        assert_eq!(x8n(1), ONE >> 8);
+        // shifting a register by `n` bytes must match feeding it `n` zeros.
+        let state = 0xfedc_ba98_7654_3210;
+        for n in 0..256 {
+            assert_eq!(
+                multiply(x8n(n), state),
+                table::update(state, &vec![0; n as usize]),
+            );
+        }
+    }
+
+    #[test]
+    fn test_x2n_table() {
+        let mut square = ONE >> 8;
+        for power in table::X2N_TABLE {
+            assert_eq!(power, square);
+            square = multiply(square, square);
+        }
+    }
+
+    #[test]
+    fn test_x2n_inv_table() {
+        // the reciprocal polynomial constant is x⁻¹.
+        assert_eq!(multiply(table::POLY, ONE >> 1), ONE);
+        for (power, inverse) in table::X2N_TABLE.iter().zip(&table::X2N_INV_TABLE) {
+            assert_eq!(multiply(*power, *inverse), ONE);
+        }
+    }
+
+    #[test]
+    fn test_x8n_inverse() {
+        for n in [0, 1, 2, 3, 1000, 4096, 123_456_789, u64::MAX] {
+            assert_eq!(multiply(x8n(n), x8n_inverse(n)), ONE, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_combine_empty() {
+        let crc = checksum(b"123456789");
+        assert_eq!(combine(crc, 0, 0), crc);
+        assert_eq!(combine(0, crc, 9), crc);
+    }
+
+    proptest! {
+        #[test]
+        fn combine_concatenation((bytes, split_index) in bytes_and_split_index()) {
+            let (left, right) = bytes.split_at(split_index);
+            prop_assert_eq!(
+                combine(checksum(left), checksum(right), right.len() as u64),
+                checksum(&bytes),
+            );
+        }
+
+        #[test]
+        fn uncombine((bytes, split_index) in bytes_and_split_index()) {
+            let (left, right) = bytes.split_at(split_index);
+            let len = right.len() as u64;
+            let whole = checksum(&bytes);
+            prop_assert_eq!(crc_of_suffix(whole, checksum(left), len), checksum(right));
+            prop_assert_eq!(crc_of_prefix(whole, checksum(right), len), checksum(left));
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Locating and correcting bit errors using the CRC-64/NVME syndrome.
+//!
+//! The syndrome of a block is the XOR of its computed and expected CRC. Since
+//! the CRC is linear, flipping bit `p` of an `n`-byte block always changes the
+//! checksum by the same value `E(n, p)`, regardless of the data. For blocks up
+//! to a few KiB these values are distinct, so a [`SyndromeTable`] mapping them
+//! back to bit positions can pinpoint (and repair) a single flipped bit, and
+//! in most cases two flipped bits.
+//!
+//! Bit positions count from the start of the block: bit `p` is the bit with
+//! value `1 << (p % 8)` in byte `p / 8`.
+//!
+//! The expected CRC is assumed to be correct.
+//!
+//! ```rust
+//! use crc64fast_nvme::{ecc::{Correction, SyndromeTable}, Digest};
+//!
+//! let mut block = [0x5a; 512];
+//! let mut c = Digest::new();
+//! c.write(&block);
+//! let expected = c.sum64();
+//!
+//! block[100] ^= 0x10;
+//!
+//! let table = SyndromeTable::new(block.len());
+//! assert_eq!(table.correct(&mut block, expected), Some(Correction::Single(804)));
+//! assert_eq!(block, [0x5a; 512]);
+//! ```
+
+use super::{table, Digest};
+
+/// The outcome of locating errors in a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Correction {
+    /// The block matches the expected CRC.
+    Intact,
+    /// A single bit was flipped, at the given position.
+    Single(usize),
+    /// Two bits were flipped, at the given positions (in ascending order).
+    Double(usize, usize),
+}
+
+/// Computes the syndrome of `data`, i.e. its CRC-64/NVME XOR the `expected` one.
+pub fn syndrome(data: &[u8], expected: u64) -> u64 {
+    let mut digest = Digest::new();
+    digest.write(data);
+    digest.sum64() ^ expected
+}
+
+/// Maps single-bit error syndromes back to bit positions, for blocks of one
+/// particular length.
+#[derive(Clone, Debug)]
+pub struct SyndromeTable {
+    len: usize,
+    // (syndrome, bit position), sorted.
+    entries: Vec<(u64, usize)>,
+}
+
+impl SyndromeTable {
+    /// Builds the syndrome table for blocks of `len` bytes.
+    ///
+    /// This takes O(n log n) time and 16 bytes of memory per bit of the block.
+    pub fn new(len: usize) -> Self {
+        let mut entries = Vec::with_capacity(len * 8);
+
+        // Flipping bit `j` of the last byte changes the register by
+        // `TABLE_0[1 << j]`; each byte further from the end shifts it by x⁸.
+        let mut errors: [u64; 8] = core::array::from_fn(|j| table::TABLE_0[1 << j]);
+        for byte in (0..len).rev() {
+            for (j, error) in errors.iter_mut().enumerate() {
+                entries.push((*error, byte * 8 + j));
+                *error = table::update(*error, &[0]);
+            }
+        }
+
+        entries.sort_unstable();
+        Self { len, entries }
+    }
+
+    /// Returns the block length this table was built for.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether this table was built for empty blocks.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit position whose flip produces `syndrome`, if exactly
+    /// one such position exists.
+    fn lookup(&self, syndrome: u64) -> Option<usize> {
+        let start = self.entries.partition_point(|(s, _)| *s < syndrome);
+        match self.entries[start..] {
+            [(s, p), (t, _), ..] if s == syndrome && t != syndrome => Some(p),
+            [(s, p)] if s == syndrome => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Locates the flipped bits from a syndrome (see [`syndrome`]).
+    ///
+    /// Returns `None` if the syndrome corresponds to neither a single-bit nor
+    /// a unique double-bit error.
+    pub fn locate_syndrome(&self, syndrome: u64) -> Option<Correction> {
+        if syndrome == 0 {
+            return Some(Correction::Intact);
+        }
+        if let Some(p) = self.lookup(syndrome) {
+            return Some(Correction::Single(p));
+        }
+
+        let mut found = None;
+        for (s, p) in &self.entries {
+            if let Some(q) = self.lookup(syndrome ^ s) {
+                if *p < q {
+                    if found.is_some() {
+                        // ambiguous
+                        return None;
+                    }
+                    found = Some(Correction::Double(*p, q));
+                }
+            }
+        }
+        found
+    }
+
+    /// Locates the flipped bits in `data`, given its `expected` CRC-64/NVME.
+    ///
+    /// Returns `None` if the errors could not be located unambiguously.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not as long as the table's block length.
+    pub fn locate(&self, data: &[u8], expected: u64) -> Option<Correction> {
+        assert_eq!(data.len(), self.len, "block length mismatch");
+        self.locate_syndrome(syndrome(data, expected))
+    }
+
+    /// Like [`locate`](Self::locate), but also flips the located bits back.
+    ///
+    /// `data` is left untouched if `None` is returned.
+    pub fn correct(&self, data: &mut [u8], expected: u64) -> Option<Correction> {
+        let correction = self.locate(data, expected)?;
+        match correction {
+            Correction::Intact => {}
+            Correction::Single(p) => flip(data, p),
+            Correction::Double(p, q) => {
+                flip(data, p);
+                flip(data, q);
+            }
+        }
+        Some(correction)
+    }
+}
+
+fn flip(data: &mut [u8], position: usize) {
+    data[position / 8] ^= 1 << (position % 8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_syndromes_match_bit_flips() {
+        let data = b"123456789";
+        let table = SyndromeTable::new(data.len());
+        for (s, p) in &table.entries {
+            let mut flipped = *data;
+            flip(&mut flipped, *p);
+            assert_eq!(syndrome(&flipped, 0xae8b14860a799888), *s);
+        }
+    }
+
+    #[test]
+    fn test_intact() {
+        let table = SyndromeTable::new(9);
+        assert_eq!(
+            table.locate(b"123456789", 0xae8b14860a799888),
+            Some(Correction::Intact)
+        );
+    }
+
+    #[test]
+    fn test_unique_single_bit_syndromes() {
+        let table = SyndromeTable::new(4096);
+        assert!(table.entries.windows(2).all(|w| w[0].0 != w[1].0));
+    }
+
+    #[test]
+    fn test_double_bit() {
+        let mut data = vec![0xa5; 512];
+        let expected = checksum(&data);
+        flip(&mut data, 3);
+        flip(&mut data, 4000);
+        let table = SyndromeTable::new(data.len());
+        assert_eq!(
+            table.correct(&mut data, expected),
+            Some(Correction::Double(3, 4000))
+        );
+        assert_eq!(data, vec![0xa5; 512]);
+    }
+
+    #[test]
+    #[should_panic(expected = "block length mismatch")]
+    fn test_length_mismatch() {
+        SyndromeTable::new(8).locate(b"123456789", 0);
+    }
+
+    fn block_and_positions() -> impl Strategy<Value = (Vec<u8>, usize, usize)> {
+        vec(any::<u8>(), 1..1024)
+            .prop_flat_map(|data| (0..data.len() * 8, 0..data.len() * 8, Just(data)))
+            .prop_map(|(p, q, data)| (data, p, q))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn corrects_single_bit((mut data, p, _) in block_and_positions()) {
+            let original = data.clone();
+            let expected = checksum(&data);
+            flip(&mut data, p);
+
+            let table = SyndromeTable::new(data.len());
+            prop_assert_eq!(table.correct(&mut data, expected), Some(Correction::Single(p)));
+            prop_assert_eq!(data, original);
+        }
+
+        #[test]
+        fn corrects_or_rejects_double_bit((mut data, p, q) in block_and_positions()) {
+            prop_assume!(p != q);
+            let original = data.clone();
+            let expected = checksum(&data);
+            flip(&mut data, p);
+            flip(&mut data, q);
+
+            let table = SyndromeTable::new(data.len());
+            match table.correct(&mut data, expected) {
+                Some(correction) => {
+                    prop_assert_eq!(correction, Correction::Double(p.min(q), p.max(q)));
+                    prop_assert_eq!(data, original);
+                }
+                None => prop_assert_ne!(data, original),
+            }
+        }
+    }
+}
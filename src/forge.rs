@@ -0,0 +1,120 @@
+//! Forging data with a chosen CRC-64/NVME value.
+//!
+//! Writing 8 bytes `b` into a register `s` gives the same result as writing 8
+//! zero bytes into `s ⊕ b` (with `b` read as a little-endian integer), so any
+//! register value can be reached from any other one by picking `b` well. The
+//! register is computed forwards over the data before the forged bytes, and
+//! backwards (from the target) over the data after them.
+
+use super::{table, Digest};
+
+/// Computes the 8 bytes which, when written over `data[offset..offset + 8]`,
+/// make the CRC-64/NVME of `data` equal to `target`.
+///
+/// # Panics
+///
+/// Panics if `offset + 8` is greater than `data.len()`.
+///
+/// ```rust
+/// use crc64fast_nvme::{forge, Digest};
+///
+/// let mut data = *b"hello, forged world!";
+/// let patch = forge(&data, 7, 0x0123_4567_89ab_cdef);
+/// data[7..15].copy_from_slice(&patch);
+///
+/// let mut c = Digest::new();
+/// c.write(&data);
+/// assert_eq!(c.sum64(), 0x0123_4567_89ab_cdef);
+/// ```
+pub fn forge(data: &[u8], offset: usize, target: u64) -> [u8; 8] {
+    let end = offset
+        .checked_add(8)
+        .filter(|end| *end <= data.len())
+        .expect("forged range is out of bounds");
+    forge_between(&data[..offset], &data[end..], target)
+}
+
+/// Computes the 8 bytes which, when inserted into `data` at `offset`, make
+/// the CRC-64/NVME of the result equal to `target`.
+///
+/// # Panics
+///
+/// Panics if `offset` is greater than `data.len()`.
+///
+/// ```rust
+/// use crc64fast_nvme::{forge_insert, Digest};
+///
+/// let data = b"hello world!";
+/// let patch = forge_insert(data, 5, 0x0123_4567_89ab_cdef);
+///
+/// let mut c = Digest::new();
+/// c.write(&data[..5]);
+/// c.write(&patch);
+/// c.write(&data[5..]);
+/// assert_eq!(c.sum64(), 0x0123_4567_89ab_cdef);
+/// ```
+pub fn forge_insert(data: &[u8], offset: usize, target: u64) -> [u8; 8] {
+    assert!(offset <= data.len(), "insertion offset is out of bounds");
+    forge_between(&data[..offset], &data[offset..], target)
+}
+
+fn forge_between(prefix: &[u8], suffix: &[u8], target: u64) -> [u8; 8] {
+    let mut digest = Digest::new();
+    digest.write(prefix);
+    let before = digest.state();
+    let after = table::unupdate(!target, suffix);
+    (table::unupdate(after, &[0; 8]) ^ before).to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_forge_standard_vector() {
+        // "123456789" with its last 8 bytes forged back to the check value.
+        let patch = forge(b"1xxxxxxxx", 1, 0xae8b14860a799888);
+        assert_eq!(&patch, b"23456789");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_forge_out_of_bounds() {
+        forge(b"1234567", 0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_forge_insert_out_of_bounds() {
+        forge_insert(b"1234567", 8, 0);
+    }
+
+    prop_compose! {
+        fn bytes_and_index()
+            (bytes in any_with::<Vec<u8>>(size_range(8..16384).lift()))
+            (index in 0..=bytes.len(), bytes in Just(bytes)) -> (Vec<u8>, usize)
+        {
+            (bytes, index)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn forge_overwrite((mut bytes, index) in bytes_and_index(), target in any::<u64>()) {
+            let offset = index.min(bytes.len() - 8);
+            let patch = forge(&bytes, offset, target);
+            bytes[offset..offset + 8].copy_from_slice(&patch);
+            prop_assert_eq!(checksum(&bytes), target);
+        }
+
+        #[test]
+        fn forge_insertion((mut bytes, offset) in bytes_and_index(), target in any::<u64>()) {
+            let patch = forge_insert(&bytes, offset, target);
+            bytes.splice(offset..offset, patch);
+            prop_assert_eq!(checksum(&bytes), target);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Using CRC-64/NVME as a [`Hasher`], e.g. for `HashMap` keys or sharding.
+//!
+//! CRC-64/NVME is not a cryptographic hash, and offers no protection against
+//! HashDoS attacks, so only use it for keys which are not attacker-controlled.
+
+use core::hash::{BuildHasher, Hasher};
+
+use super::{pclmulqdq, Computer, Digest};
+
+impl Hasher for Digest {
+    fn finish(&self) -> u64 {
+        self.sum64()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        Digest::write(self, bytes);
+    }
+}
+
+/// A [`BuildHasher`] which creates [`Digest`]s.
+///
+/// Runtime CPU feature detection is performed once, when the builder is
+/// created, rather than for every hasher.
+///
+/// ```rust
+/// use crc64fast_nvme::BuildCrc64Nvme;
+/// use std::collections::HashMap;
+///
+/// let mut map: HashMap<u64, &str, _> = HashMap::with_hasher(BuildCrc64Nvme::new());
+/// map.insert(42, "block");
+/// assert_eq!(map[&42], "block");
+/// ```
+#[derive(Clone, Copy)]
+pub struct BuildCrc64Nvme {
+    computer: &'static Computer,
+}
+
+impl BuildCrc64Nvme {
+    /// Creates a new `BuildCrc64Nvme`, selecting the fastest available algorithm.
+    pub fn new() -> Self {
+        Self {
+            computer: pclmulqdq::get_update(),
+        }
+    }
+}
+
+impl Default for BuildCrc64Nvme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for BuildCrc64Nvme {
+    type Hasher = Digest;
+
+    fn build_hasher(&self) -> Digest {
+        Digest {
+            computer: self.computer,
+            state: !0,
+            amount: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    #[test]
+    fn test_hasher_matches_sum64() {
+        let mut hasher = BuildCrc64Nvme::new().build_hasher();
+        Hasher::write(&mut hasher, b"hello ");
+        Hasher::write(&mut hasher, b"world!");
+        assert_eq!(hasher.finish(), 0xd9160d1fa8e418e3);
+    }
+
+    #[test]
+    fn test_build_hasher_is_deterministic() {
+        let build = BuildCrc64Nvme::new();
+        let hash = |key: &str| {
+            let mut hasher = build.build_hasher();
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash("block-1"), hash("block-1"));
+        assert_ne!(hash("block-1"), hash("block-2"));
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let mut map = HashMap::with_hasher(BuildCrc64Nvme::default());
+        for i in 0..1000u64 {
+            map.insert(i, i * 2);
+        }
+        for i in 0..1000u64 {
+            assert_eq!(map[&i], i * 2);
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Checksumming data as it flows through [`Read`] and [`Write`] streams.
+
+use std::io::{self, BufRead, ErrorKind, IoSlice, Read, Write};
+
+use super::Digest;
+
+impl Digest {
+    /// Writes the concatenation of `bufs` into the digest.
+    ///
+    /// Unlike calling [`write`](Self::write) for each buffer, the SIMD fold
+    /// state is carried across buffer boundaries, so many small or unaligned
+    /// buffers are processed about as fast as one contiguous one.
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::Digest;
+    /// use std::io::IoSlice;
+    ///
+    /// let mut c = Digest::new();
+    /// c.write_vectored(&[IoSlice::new(b"hello "), IoSlice::new(b"world!")]);
+    /// assert_eq!(c.sum64(), 0xd9160d1fa8e418e3);
+    /// ```
+    pub fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) {
+        let mut bytes = bufs.iter().map(|buf| &**buf);
+        unsafe {
+            self.state = (self.computer.update_vectored)(self.state, &mut bytes);
+        }
+        self.amount += bufs.iter().map(|buf| buf.len() as u64).sum::<u64>();
+    }
+}
+
+/// A reader which computes the CRC-64/NVME of the data read through it.
+///
+/// ```rust
+/// use crc64fast_nvme::CrcReader;
+/// use std::io::Read;
+///
+/// let mut reader = CrcReader::new(&b"hello world!"[..]);
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data).unwrap();
+/// assert_eq!(reader.sum64(), 0xd9160d1fa8e418e3);
+/// assert_eq!(reader.amount(), 12);
+/// ```
+#[derive(Clone)]
+pub struct CrcReader<R> {
+    inner: R,
+    digest: Digest,
+}
+
+impl<R> CrcReader<R> {
+    /// Wraps `inner`, starting a new checksum.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+        }
+    }
+
+    /// Returns the CRC-64/NVME of the data read so far.
+    pub fn sum64(&self) -> u64 {
+        self.digest.sum64()
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn amount(&self) -> u64 {
+        self.digest.amount
+    }
+
+    /// Returns the digest of the data read so far.
+    pub fn digest(&self) -> &Digest {
+        &self.digest
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Data read directly from it is not checksummed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for CrcReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.digest.write(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for CrcReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The buffer was just filled, so this does not read anything.
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.digest.write(&buf[..amt]);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// A writer which computes the CRC-64/NVME of the data written through it.
+///
+/// ```rust
+/// use crc64fast_nvme::CrcWriter;
+/// use std::io::Write;
+///
+/// let mut writer = CrcWriter::new(Vec::new());
+/// writer.write_all(b"hello ").unwrap();
+/// writer.write_all(b"world!").unwrap();
+/// assert_eq!(writer.sum64(), 0xd9160d1fa8e418e3);
+/// assert_eq!(writer.into_inner(), b"hello world!");
+/// ```
+#[derive(Clone)]
+pub struct CrcWriter<W> {
+    inner: W,
+    digest: Digest,
+}
+
+impl<W> CrcWriter<W> {
+    /// Wraps `inner`, starting a new checksum.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+        }
+    }
+
+    /// Returns the CRC-64/NVME of the data written so far.
+    pub fn sum64(&self) -> u64 {
+        self.digest.sum64()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn amount(&self) -> u64 {
+        self.digest.amount
+    }
+
+    /// Returns the digest of the data written so far.
+    pub fn digest(&self) -> &Digest {
+        &self.digest
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Data written directly to it is not checksummed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.digest.write(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader which checks the CRC-64/NVME of the data read through it, and
+/// fails with [`ErrorKind::InvalidData`] at the end of the stream if it does
+/// not match.
+///
+/// The expected checksum is either given up front, or read from an 8-byte
+/// little-endian trailer at the end of the stream (see
+/// [`append_crc`](crate::append_crc)), which is not returned to the caller.
+///
+/// By default, data is returned as soon as it is read, so the caller sees all
+/// of it before the mismatch is reported. With
+/// [`hold_back`](Self::hold_back), the data from the last read of the
+/// underlying reader is only returned once it is known to be valid.
+///
+/// ```rust
+/// use crc64fast_nvme::VerifyingReader;
+/// use std::io::{ErrorKind, Read};
+///
+/// let mut reader = VerifyingReader::new(&b"hello world!"[..], 0xd9160d1fa8e418e3);
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data).unwrap();
+/// assert_eq!(data, b"hello world!");
+///
+/// let mut reader = VerifyingReader::new(&b"hello world?"[..], 0xd9160d1fa8e418e3);
+/// let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::InvalidData);
+/// ```
+pub struct VerifyingReader<R> {
+    inner: R,
+    digest: Digest,
+    expected: Option<u64>,
+    hold_back: bool,
+    // Data read but not yet returned; `buf[..ready]` can be returned.
+    buf: Vec<u8>,
+    ready: usize,
+    // Whether the checksum matched, once the end of the stream is reached.
+    verified: Option<bool>,
+}
+
+impl<R> VerifyingReader<R> {
+    /// Wraps `inner`, expecting its data to have the CRC-64/NVME `expected`.
+    pub fn new(inner: R, expected: u64) -> Self {
+        Self::with_expected(inner, Some(expected))
+    }
+
+    /// Wraps `inner`, expecting its data to end with the little-endian
+    /// CRC-64/NVME of the data before it.
+    pub fn with_trailer(inner: R) -> Self {
+        Self::with_expected(inner, None)
+    }
+
+    fn with_expected(inner: R, expected: Option<u64>) -> Self {
+        Self {
+            inner,
+            digest: Digest::new(),
+            expected,
+            hold_back: false,
+            buf: Vec::new(),
+            ready: 0,
+            verified: None,
+        }
+    }
+
+    /// Sets whether to hold back the data from the last read of the
+    /// underlying reader until the checksum is verified.
+    pub fn hold_back(mut self, hold_back: bool) -> Self {
+        self.hold_back = hold_back;
+        self
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwraps the underlying reader.
+    ///
+    /// Data read from it but not yet returned is discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the number of bytes to keep after the data, i.e. the trailer.
+    fn reserved(&self) -> usize {
+        if self.expected.is_some() {
+            0
+        } else {
+            8
+        }
+    }
+
+    fn verify(&self) -> bool {
+        match self.expected {
+            Some(expected) => self.digest.sum64() == expected,
+            None => self.digest.is_residue_valid(),
+        }
+    }
+}
+
+impl<R: Read> VerifyingReader<R> {
+    /// Reads more data from the underlying reader into the buffer.
+    fn fill(&mut self, len: usize) -> io::Result<()> {
+        let start = self.buf.len();
+        self.buf.resize(start + len, 0);
+        let n = match self.inner.read(&mut self.buf[start..]) {
+            Ok(n) => n,
+            Err(e) => {
+                self.buf.truncate(start);
+                return Err(e);
+            }
+        };
+        self.buf.truncate(start + n);
+        self.digest.write(&self.buf[start..]);
+
+        let end = if n == 0 {
+            let verified = self.verify();
+            self.verified = Some(verified);
+            if verified {
+                self.buf.len()
+            } else {
+                0
+            }
+        } else if self.hold_back {
+            start
+        } else {
+            self.buf.len()
+        };
+        self.ready = end.min(self.buf.len().saturating_sub(self.reserved()));
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.ready > 0 {
+                let n = out.len().min(self.ready);
+                out[..n].copy_from_slice(&self.buf[..n]);
+                self.buf.drain(..n);
+                self.ready -= n;
+                return Ok(n);
+            }
+            match self.verified {
+                Some(true) => return Ok(0),
+                Some(false) => {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        "CRC-64/NVME checksum mismatch",
+                    ))
+                }
+                None if out.is_empty() => return Ok(0),
+                None => self.fill(out.len().max(self.reserved()))?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+
+    /// A writer which accepts at most `limit` bytes per call.
+    struct ShortWriter {
+        data: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit);
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A reader which returns at most `limit` bytes per call.
+    struct ShortReader<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    /// Reads from `reader` until it fails, returning the data read so far.
+    fn read_until_error(reader: &mut impl Read) -> (Vec<u8>, io::Error) {
+        let mut data = Vec::new();
+        let mut buf = [0; 7];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => panic!("no error at the end of the stream"),
+                Ok(n) => data.extend_from_slice(&buf[..n]),
+                Err(e) => return (data, e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_verifying_reader_trailer() {
+        let mut record = b"hello world!".to_vec();
+        crate::append_crc(&mut record);
+        let mut data = Vec::new();
+        VerifyingReader::with_trailer(&record[..])
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, b"hello world!");
+
+        record[3] ^= 1;
+        let mut reader = VerifyingReader::with_trailer(&record[..]);
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // too short to hold a trailer
+        let mut reader = VerifyingReader::with_trailer(&record[..7]);
+        let (data, error) = read_until_error(&mut reader);
+        assert!(data.is_empty());
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_verifying_reader_hold_back() {
+        let data = b"the final chunk is corrupt";
+        let reader = ShortReader {
+            data: &data[..],
+            limit: 6,
+        };
+
+        // the underlying reader returns 6 bytes at a time, so the last 2 are held back.
+        let mut reader =
+            VerifyingReader::new(reader, checksum(b"the final chunk is ok!")).hold_back(true);
+        let (read, error) = read_until_error(&mut reader);
+        assert_eq!(read, b"the final chunk is corru");
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // the error is sticky
+        assert_eq!(
+            reader.read(&mut [0; 8]).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_write_vectored_small_buffers() {
+        // blocks straddle many buffers
+        let bytes: Vec<u8> = (0..4096u32).map(|i| (i * 7) as u8).collect();
+        let bufs: Vec<_> = bytes.chunks(3).map(IoSlice::new).collect();
+        let mut digest = Digest::new();
+        digest.write_vectored(&bufs);
+        assert_eq!(digest.sum64(), checksum(&bytes));
+        assert_eq!(digest.amount, 4096);
+
+        let mut digest = Digest::new();
+        digest.write_vectored(&[]);
+        assert_eq!(digest.sum64(), checksum(b""));
+    }
+
+    #[test]
+    fn test_buf_read() {
+        let data = b"first line\nsecond line\nno newline";
+        let mut reader = CrcReader::new(BufReader::with_capacity(4, &data[..]));
+        let mut lines = 0;
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() != 0 {
+            lines += 1;
+        }
+        assert_eq!(lines, 3);
+        assert_eq!(line.as_bytes(), data);
+        assert_eq!(reader.sum64(), checksum(data));
+        assert_eq!(reader.amount(), data.len() as u64);
+    }
+
+    #[test]
+    fn test_short_writes() {
+        let mut writer = CrcWriter::new(ShortWriter {
+            data: Vec::new(),
+            limit: 3,
+        });
+        assert_eq!(writer.write(b"hello world!").unwrap(), 3);
+        assert_eq!(writer.sum64(), checksum(b"hel"));
+        writer.write_all(b"lo world!").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.sum64(), 0xd9160d1fa8e418e3);
+        assert_eq!(writer.amount(), 12);
+        assert_eq!(writer.into_inner().data, b"hello world!");
+    }
+
+    proptest! {
+        #[test]
+        fn copy_through(bytes in any_with::<Vec<u8>>(size_range(..65536).lift()), capacity in 1..1024usize) {
+            let mut reader = CrcReader::new(BufReader::with_capacity(capacity, &bytes[..]));
+            let mut writer = CrcWriter::new(Vec::new());
+            io::copy(&mut reader, &mut writer).unwrap();
+            prop_assert_eq!(reader.sum64(), checksum(&bytes));
+            prop_assert_eq!(writer.sum64(), checksum(&bytes));
+            prop_assert_eq!(reader.amount(), bytes.len() as u64);
+            prop_assert_eq!(writer.into_inner(), bytes);
+        }
+
+        #[test]
+        fn write_vectored(bytes in any_with::<Vec<u8>>(size_range(..16384).lift()), mut cuts in proptest::collection::vec(any::<usize>(), ..64)) {
+            for cut in &mut cuts {
+                *cut %= bytes.len() + 1;
+            }
+            cuts.sort_unstable();
+            let mut bufs = Vec::new();
+            let mut start = 0;
+            for cut in cuts.into_iter().chain([bytes.len()]) {
+                bufs.push(IoSlice::new(&bytes[start..cut]));
+                start = cut;
+            }
+
+            for mut digest in [Digest::new(), Digest::new_table()] {
+                digest.write(b"prefix");
+                let mut expected = digest.clone();
+                expected.write(&bytes);
+                digest.write_vectored(&bufs);
+                prop_assert_eq!(digest.sum64(), expected.sum64());
+                prop_assert_eq!(digest.amount, expected.amount);
+            }
+        }
+
+        #[test]
+        fn verifying_reader(
+            bytes in any_with::<Vec<u8>>(size_range(..16384).lift()),
+            limit in 1..1024usize,
+            trailer: bool,
+            hold_back: bool,
+            corrupt in proptest::option::of(any::<usize>()),
+        ) {
+            let mut record = bytes.clone();
+            let expected = checksum(&bytes);
+            if trailer {
+                crate::append_crc(&mut record);
+            }
+            if let Some(index) = corrupt {
+                if record.is_empty() {
+                    return Ok(());
+                }
+                let index = index % record.len();
+                record[index] ^= 1;
+            }
+
+            let reader = ShortReader { data: &record, limit };
+            let mut reader = if trailer {
+                VerifyingReader::with_trailer(reader)
+            } else {
+                VerifyingReader::new(reader, expected)
+            }
+            .hold_back(hold_back);
+
+            if corrupt.is_none() {
+                let mut data = Vec::new();
+                reader.read_to_end(&mut data).unwrap();
+                prop_assert_eq!(data, bytes);
+            } else {
+                let (data, error) = read_until_error(&mut reader);
+                prop_assert_eq!(error.kind(), ErrorKind::InvalidData);
+                prop_assert!(record.starts_with(&data));
+                if hold_back || trailer {
+                    prop_assert!(data.len() < record.len());
+                }
+            }
+        }
+    }
+}
@@ -19,13 +19,11 @@
 //! let checksum = c.sum64();
 //! assert_eq!(checksum, 0xd9160d1fa8e418e3);
 //! ```
-//! ### C-compatible shared library example (PHP)
+//! ### C-compatible shared library
 //!
-//! ```php
-//! $digest = $ffi->digest_new();
-//! $ffi->digest_write($digest, 'hello world!', 12);
-//! $checksum = $ffi->digest_sum64($digest); // 0xd9160d1fa8e418e3
-//! ```
+//! The shared library is built from the `crc64fast-nvme-ffi` crate in the
+//! [`ffi`](https://github.com/awesomized/crc64fast-nvme/tree/main/ffi)
+//! directory, so that this crate can be used by `no_std` binaries.
 //!
 //! Tracking links for unstable features used here with the
 //! [experimental VPCLMULQDQ](https://github.com/awesomized/crc64fast-nvme?tab=readme-ov-file#experimental-vector-carry-less-multiplication-of-quadwords-vpclmulqdq-support)
@@ -35,119 +33,223 @@
 //! - [link_llvm_intrinsics](https://github.com/rust-lang/rust/issues/29602)
 //! - [avx512_target_feature](https://github.com/rust-lang/rust/issues/111137)
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(
     feature = "vpclmulqdq",
     feature(avx512_target_feature, stdarch_x86_avx512)
 )]
 
-use std::os::raw::c_char;
-use std::slice;
-
+#[cfg(feature = "tokio")]
+mod async_io;
+mod batch;
+#[cfg(feature = "http-body")]
+mod body;
+mod checksum;
+#[cfg(feature = "std")]
+mod chunked;
+mod combine;
+#[cfg(feature = "std")]
+pub mod ecc;
+mod forge;
+mod hasher;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod patch;
 mod pclmulqdq;
+mod rolling;
+#[cfg(feature = "digest")]
+mod rust_crypto;
+mod state;
 mod table;
+mod trailer;
+
+#[cfg(feature = "tokio")]
+pub use async_io::{AsyncCrcReader, AsyncCrcWriter};
+pub use batch::checksum_batch;
+#[cfg(feature = "http-body")]
+pub use body::{ChecksumBody, CHECKSUM_TRAILER};
+pub use checksum::{Crc64Nvme, ParseCrc64NvmeError};
+#[cfg(feature = "std")]
+pub use chunked::{ChunkError, ChunkedDigest};
+pub use combine::{combine, crc_of_prefix, crc_of_suffix};
+pub use forge::{forge, forge_insert};
+pub use hasher::BuildCrc64Nvme;
+#[cfg(feature = "std")]
+pub use io::{CrcReader, CrcWriter, VerifyingReader};
+#[cfg(feature = "rayon")]
+pub use parallel::par_checksum;
+pub use patch::patch_crc;
+pub use rolling::RollingDigest;
+pub use state::DIGEST_STATE_LEN;
+pub use table::unupdate;
+#[cfg(feature = "std")]
+pub use trailer::append_crc;
+pub use trailer::verify_with_trailer;
 
 type UpdateFn = unsafe fn(u64, &[u8]) -> u64;
+type UpdateVectoredFn = unsafe fn(u64, &mut dyn Iterator<Item = &[u8]>) -> u64;
+type UpdateManyFn = unsafe fn(&mut [u64], &[&[u8]]);
+
+/// The update functions of an algorithm. There is one instance for each, so
+/// that they are shared by reference.
+pub(crate) struct Computer {
+    update: UpdateFn,
+    /// Updates over several buffers, as if they were concatenated.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    update_vectored: UpdateVectoredFn,
+    /// Updates several independent states, each over its own buffer.
+    update_many: UpdateManyFn,
+}
 
 /// Represents an in-progress CRC-64 computation.
 #[derive(Clone)]
 pub struct Digest {
-    computer: UpdateFn,
+    computer: &'static Computer,
     state: u64,
+    amount: u64,
 }
 
-// begin C-compatible shared library methods
-
-/// Opaque type for C for use in FFI (C-compatible shared library)
-#[repr(C)]
-pub struct DigestHandle(*mut Digest);
-
-/// Creates a new Digest (C-compatible shared library)
-#[no_mangle]
-pub extern "C" fn digest_new() -> *mut DigestHandle {
-    let digest = Box::new(Digest::new());
-    let handle = Box::new(DigestHandle(Box::into_raw(digest)));
-    Box::into_raw(handle)
-}
-
-/// Writes data to the Digest (C-compatible shared library)
-///
-/// # Safety
-///
-/// Uses unsafe method calls
-#[no_mangle]
-pub unsafe extern "C" fn digest_write(handle: *mut DigestHandle, data: *const c_char, len: usize) {
-    if handle.is_null() || data.is_null() {
-        return;
-    }
-
-    let digest = &mut *(*handle).0;
-    let bytes = slice::from_raw_parts(data as *const u8, len);
-    digest.write(bytes);
-}
-
-/// Calculates the CRC-64 checksum from the Digest (C-compatible shared library)
-///
-/// # Safety
-///
-/// Uses unsafe method calls
-#[no_mangle]
-pub unsafe extern "C" fn digest_sum64(handle: *const DigestHandle) -> u64 {
-    if handle.is_null() {
-        return 0;
-    }
-
-    let digest = &*(*handle).0;
-    digest.sum64()
-}
-
-/// Frees the Digest (C-compatible shared library)
-///
-/// # Safety
-///
-/// Uses unsafe method calls
-#[no_mangle]
-pub unsafe extern "C" fn digest_free(handle: *mut DigestHandle) {
-    if !handle.is_null() {
-        let handle = Box::from_raw(handle);
-        let _ = Box::from_raw(handle.0);
-    }
-}
-
-// end C-compatible shared library methods
-
 impl Digest {
     /// Creates a new `Digest`.
     ///
     /// It will perform runtime CPU feature detection to determine which
-    /// algorithm to choose.
+    /// algorithm to choose. Without the `std` feature, SIMD is only used if
+    /// the required target features are enabled at compile time (e.g. with
+    /// `-C target-feature=+pclmulqdq,+sse4.1`), and the table-based algorithm
+    /// is used otherwise.
     pub fn new() -> Self {
         Self {
             computer: pclmulqdq::get_update(),
             state: !0,
+            amount: 0,
         }
     }
 
     /// Creates a new `Digest` using table-based algorithm.
-    pub fn new_table() -> Self {
+    ///
+    /// This can be used in const contexts, together with
+    /// [`const_write`](Self::const_write).
+    pub const fn new_table() -> Self {
         Self {
-            computer: table::update,
+            computer: table::COMPUTER,
             state: !0,
+            amount: 0,
         }
     }
 
+    /// Creates a new `Digest` which continues from a previously computed
+    /// CRC-64/NVME value, as if the data behind `crc` had been written to it.
+    ///
+    /// Like [`new`](Self::new), it performs runtime CPU feature detection.
+    /// Only the bytes written from now on count towards the length used by
+    /// [`combine`](Self::combine).
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::Digest;
+    ///
+    /// let mut c = Digest::new();
+    /// c.write(b"hello ");
+    ///
+    /// let mut c = Digest::with_initial(c.sum64());
+    /// c.write(b"world!");
+    /// assert_eq!(c.sum64(), 0xd9160d1fa8e418e3);
+    /// ```
+    pub fn with_initial(crc: u64) -> Self {
+        Self::from_state(!crc)
+    }
+
+    /// Creates a new `Digest` from a raw CRC register value (see [`state`](Self::state)).
+    ///
+    /// Like [`new`](Self::new), it performs runtime CPU feature detection.
+    pub fn from_state(state: u64) -> Self {
+        Self {
+            computer: pclmulqdq::get_update(),
+            state,
+            amount: 0,
+        }
+    }
+
+    /// Returns the raw CRC register value, before the final XOR.
+    ///
+    /// This is the bitwise NOT of [`sum64`](Self::sum64).
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Resets the digest to its initial state, keeping the selected algorithm.
+    pub fn reset(&mut self) {
+        self.state = !0;
+        self.amount = 0;
+    }
+
     /// Writes some data into the digest.
     pub fn write(&mut self, bytes: &[u8]) {
         unsafe {
-            self.state = (self.computer)(self.state, bytes);
+            self.state = (self.computer.update)(self.state, bytes);
         }
+        self.amount += bytes.len() as u64;
+    }
+
+    /// Writes some data into the digest, in a const context.
+    ///
+    /// This processes one byte at a time, so prefer [`write`](Self::write)
+    /// outside of const contexts.
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::Digest;
+    ///
+    /// const CHECKSUM: u64 = Digest::new_table()
+    ///     .const_write(b"hello ")
+    ///     .const_write(b"world!")
+    ///     .sum64();
+    /// assert_eq!(CHECKSUM, 0xd9160d1fa8e418e3);
+    /// ```
+    #[must_use]
+    pub const fn const_write(mut self, bytes: &[u8]) -> Self {
+        self.state = table::update_const(self.state, bytes);
+        self.amount += bytes.len() as u64;
+        self
+    }
+
+    /// Writes `len` zero bytes into the digest.
+    ///
+    /// This takes O(log(len)) time and does not touch any memory, which makes
+    /// it suitable for sparse or zero-padded data.
+    pub fn write_zeros(&mut self, len: u64) {
+        self.state = combine::shift(self.state, len);
+        self.amount += len;
+    }
+
+    /// Combines the checksum of `other` into this digest, as if the data
+    /// written to `other` had been written to `self` after its own data.
+    pub fn combine(&mut self, other: &Self) {
+        self.state = !combine::combine(self.sum64(), other.sum64(), other.amount);
+        self.amount += other.amount;
     }
 
     /// Computes the current CRC-64/NVME value.
-    pub fn sum64(&self) -> u64 {
+    pub const fn sum64(&self) -> u64 {
         !self.state
     }
 }
 
+/// Computes the CRC-64/NVME of `bytes` in a const context.
+///
+/// This processes one byte at a time, so prefer [`Digest`] outside of const
+/// contexts.
+///
+/// ```rust
+/// use crc64fast_nvme::crc64_nvme;
+///
+/// const CHECKSUM: u64 = crc64_nvme(b"123456789");
+/// assert_eq!(CHECKSUM, 0xae8b14860a799888);
+/// ```
+pub const fn crc64_nvme(bytes: &[u8]) -> u64 {
+    !table::update_const(!0, bytes)
+}
+
 impl Default for Digest {
     fn default() -> Self {
         Self::new()
@@ -159,7 +261,6 @@ mod tests {
     use super::*;
     use proptest::collection::size_range;
     use proptest::prelude::*;
-    use std::ptr;
 
     // CRC-64/NVME
     //
@@ -168,7 +269,7 @@ mod tests {
     // https://nvmexpress.org/wp-content/uploads/NVM-Express-NVM-Command-Set-Specification-1.0d-2023.12.28-Ratified.pdf
     //
     // Note: The Check value published in the spec is incorrect (Section 5.2.1.3.4, Figure 120, page 83).
-    const CRC_NVME: crc::Algorithm<u64> = crc::Algorithm {
+    pub(crate) const CRC_NVME: crc::Algorithm<u64> = crc::Algorithm {
         width: 64,
         poly: 0xAD93D23594C93659,
         init: 0xFFFFFFFFFFFFFFFF,
@@ -176,9 +277,36 @@ mod tests {
         refout: true,
         xorout: 0xFFFFFFFFFFFFFFFF,
         check: 0xae8b14860a799888,
-        residue: 0x0000000000000000,
+        residue: 0xf310303b2b6f6e42,
     };
 
+    /// Computes the CRC-64/NVME of `bytes` with a fresh [`Digest`].
+    pub(crate) fn checksum(bytes: &[u8]) -> u64 {
+        let mut digest = Digest::new();
+        digest.write(bytes);
+        digest.sum64()
+    }
+
+    /// Creates a single-threaded runtime for the async tests, with IO enabled
+    /// for those running an HTTP server.
+    #[cfg(any(feature = "tokio", feature = "http-body"))]
+    pub(crate) fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_size() {
+        // the algorithm is shared by reference, rather than copied into each
+        // `Digest`
+        assert_eq!(
+            core::mem::size_of::<Digest>(),
+            core::mem::size_of::<usize>() + 16
+        );
+    }
+
     #[test]
     fn test_standard_vectors() {
         static CASES: &[(&[u8], u64)] = &[
@@ -214,15 +342,44 @@ mod tests {
             let mut hasher = Digest::new();
             hasher.write(input);
             assert_eq!(hasher.sum64(), *result, "test case {:x?}", input);
+            assert_eq!(crc64_nvme(input), *result, "test case {:x?}", input);
+            let hasher = Digest::new_table().const_write(input);
+            assert_eq!(hasher.sum64(), *result, "test case {:x?}", input);
         }
     }
 
+    #[test]
+    fn test_const_eval() {
+        const CHECK: u64 = crc64_nvme(b"123456789");
+        const ZEROS: u64 = crc64_nvme(&[0; 4096]);
+        const HELLO: Digest = Digest::new_table()
+            .const_write(b"hello ")
+            .const_write(b"world!");
+        const HELLO_SUM: u64 = HELLO.sum64();
+        assert_eq!(CHECK, 0xae8b14860a799888);
+        assert_eq!(ZEROS, 0x6482d367eb22b64e);
+        assert_eq!(HELLO_SUM, 0xd9160d1fa8e418e3);
+
+        // a const digest can keep going at runtime.
+        let mut hasher = HELLO;
+        hasher.write(b"123456789");
+        let mut expected = Digest::new();
+        expected.write(b"hello world!123456789");
+        assert_eq!(hasher.sum64(), expected.sum64());
+    }
+
+    #[test]
+    fn test_residue() {
+        assert_eq!(table::RESIDUE, CRC_NVME.residue);
+        assert_eq!(table::update(!0, &[0; 8]), CRC_NVME.residue);
+    }
+
     fn any_buffer() -> <Box<[u8]> as Arbitrary>::Strategy {
         any_with::<Box<[u8]>>(size_range(..65536).lift())
     }
 
     prop_compose! {
-        fn bytes_and_split_index()
+        pub(crate) fn bytes_and_split_index()
             (bytes in any_buffer())
             (index in 0..=bytes.len(), bytes in Just(bytes)) -> (Box<[u8]>, usize)
         {
@@ -256,140 +413,86 @@ mod tests {
         }
 
         #[test]
-        fn state_cloning(left in any_buffer(), right in any_buffer()) {
+        fn initial_value((bytes, split_index) in bytes_and_split_index()) {
+            let (left, right) = bytes.split_at(split_index);
             let mut hasher_1 = Digest::new();
-            hasher_1.write(&left);
-            let mut hasher_2 = hasher_1.clone();
-            hasher_1.write(&right);
-            hasher_2.write(&right);
+            hasher_1.write(left);
+            let mut hasher_2 = Digest::with_initial(hasher_1.sum64());
+            let mut hasher_3 = Digest::from_state(hasher_1.state());
+            hasher_1.write(right);
+            hasher_2.write(right);
+            hasher_3.write(right);
             prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
+            prop_assert_eq!(hasher_1.sum64(), hasher_3.sum64());
+            prop_assert_eq!(hasher_1.state(), !hasher_1.sum64());
         }
-    }
-
-    // test the FFI Digest functions
-    #[test]
-    fn test_ffi_digest_lifecycle() {
-        unsafe {
-            // Create new digest
-            let handle = digest_new();
-            assert!(!handle.is_null(), "Digest creation failed");
 
-            // Write some data
-            let data = b"hello world!";
-            digest_write(handle, data.as_ptr() as *const c_char, data.len());
-
-            // Get sum and verify against known value
-            let sum = digest_sum64(handle);
-            assert_eq!(sum, 0xd9160d1fa8e418e3, "CRC64 calculation incorrect");
-
-            // Clean up
-            digest_free(handle);
+        #[test]
+        fn reset(left in any_buffer(), right in any_buffer()) {
+            for mut hasher_1 in [Digest::new(), Digest::new_table()] {
+                hasher_1.write(&left);
+                hasher_1.reset();
+                hasher_1.write(&right);
+                let mut hasher_2 = Digest::new();
+                hasher_2.write(&right);
+                prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
+            }
         }
-    }
-
-    #[test]
-    fn test_ffi_null_handling() {
-        unsafe {
-            // Test null handle with write
-            digest_write(ptr::null_mut(), b"test".as_ptr() as *const c_char, 4);
-
-            // Test null data with valid handle
-            let handle = digest_new();
-            digest_write(handle, ptr::null(), 0);
 
-            // Test null handle with sum64
-            let sum = digest_sum64(ptr::null());
-            assert_eq!(sum, 0, "Null handle should return 0");
-
-            // Clean up
-            digest_free(handle);
-        }
-    }
+        #[test]
+        fn write_zeros(bytes in any_buffer(), len in 0..65536usize) {
+            let zeros = vec![0; len];
 
-    #[test]
-    fn test_ffi_empty_data() {
-        unsafe {
-            let handle = digest_new();
+            let mut hasher_1 = Digest::new();
+            hasher_1.write(&bytes);
+            hasher_1.write_zeros(len as u64);
 
-            // Write empty data
-            digest_write(handle, b"".as_ptr() as *const c_char, 0);
-            let sum = digest_sum64(handle);
-            assert_eq!(sum, 0, "Empty data should produce 0");
+            let mut hasher_2 = Digest::new();
+            hasher_2.write(&bytes);
+            hasher_2.write(&zeros);
 
-            digest_free(handle);
+            let state = table::update(!0, &bytes);
+            prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
+            prop_assert_eq!(hasher_1.sum64(), !table::update(state, &zeros));
         }
-    }
 
-    #[test]
-    fn test_ffi_binary_data() {
-        unsafe {
-            let handle = digest_new();
-
-            // Test with binary data including null bytes
-            let data = [0u8, 1, 2, 3, 0, 4, 5, 0, 6];
-            digest_write(handle, data.as_ptr() as *const c_char, data.len());
-
-            // Write additional data to test streaming
-            let more_data = [7u8, 8, 9];
-            digest_write(handle, more_data.as_ptr() as *const c_char, more_data.len());
+        #[test]
+        fn digest_combine((bytes, split_index) in bytes_and_split_index()) {
+            let (left, right) = bytes.split_at(split_index);
+            let mut hasher_1 = Digest::new();
+            hasher_1.write(left);
+            let mut hasher_2 = Digest::new();
+            hasher_2.write(right);
+            hasher_1.combine(&hasher_2);
 
-            let sum = digest_sum64(handle);
-            assert_ne!(sum, 0, "Binary data should produce non-zero CRC");
+            let mut hasher_3 = Digest::new();
+            hasher_3.write(&bytes);
+            prop_assert_eq!(hasher_1.sum64(), hasher_3.sum64());
 
-            digest_free(handle);
+            // the combined digest keeps going as if it had seen all the data.
+            hasher_1.write(left);
+            hasher_3.write(left);
+            prop_assert_eq!(hasher_1.sum64(), hasher_3.sum64());
         }
-    }
 
-    #[test]
-    fn test_ffi_large_vectors() {
-        unsafe {
-            let zeros = vec![0u8; 4096];
-            let ones = vec![255u8; 4096];
-
-            let handle = digest_new();
-            digest_write(handle, zeros.as_ptr() as *const c_char, zeros.len());
-            let sum = digest_sum64(handle);
-            assert_eq!(sum, 0x6482d367eb22b64e, "Failed on 4096 zeros");
-            digest_free(handle);
-
-            let handle = digest_new();
-            digest_write(handle, ones.as_ptr() as *const c_char, ones.len());
-            let sum = digest_sum64(handle);
-            assert_eq!(sum, 0xc0ddba7302eca3ac, "Failed on 4096 ones");
-            digest_free(handle);
-        }
-    }
-
-    #[test]
-    fn test_ffi_standard_strings() {
-        unsafe {
-            let test_cases: Vec<(&[u8], u64)> = vec![(b"123456789", 0xae8b14860a799888), (b"", 0)];
-
-            for (input, expected) in test_cases {
-                let handle = digest_new();
-                digest_write(handle, input.as_ptr() as *const c_char, input.len());
-                let sum = digest_sum64(handle);
-                assert_eq!(sum, expected, "Failed on test vector: {:?}", input);
-                digest_free(handle);
-            }
+        #[test]
+        fn unupdate_inverts_update((bytes, split_index) in bytes_and_split_index(), state in any::<u64>()) {
+            let (left, right) = bytes.split_at(split_index);
+            let middle = table::update(state, left);
+            let end = table::update(middle, right);
+            prop_assert_eq!(unupdate(end, right), middle);
+            prop_assert_eq!(unupdate(end, &bytes), state);
+            prop_assert_eq!(table::update(unupdate(state, &bytes), &bytes), state);
         }
-    }
-
-    #[test]
-    fn test_ffi_incremental_update() {
-        unsafe {
-            let handle = digest_new();
-
-            // Write data incrementally
-            let data = "hello world!";
-            for byte in data.bytes() {
-                digest_write(handle, &byte as *const u8 as *const c_char, 1);
-            }
-
-            let sum = digest_sum64(handle);
-            assert_eq!(sum, 0xd9160d1fa8e418e3, "Incremental update failed");
 
-            digest_free(handle);
+        #[test]
+        fn state_cloning(left in any_buffer(), right in any_buffer()) {
+            let mut hasher_1 = Digest::new();
+            hasher_1.write(&left);
+            let mut hasher_2 = hasher_1.clone();
+            hasher_1.write(&right);
+            hasher_2.write(&right);
+            prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
         }
     }
 }
@@ -0,0 +1,70 @@
+//! Checksumming large buffers on several threads.
+//!
+//! The buffer is split into chunks which are checksummed independently, on
+//! the rayon thread pool, and the per-chunk CRCs are then merged with
+//! [`combine`] in order.
+
+use rayon::prelude::*;
+
+use super::{combine::combine, pclmulqdq, Computer};
+
+/// The smallest chunk handed to a thread; below this, the cost of
+/// combining and scheduling outweighs the gain.
+const MIN_CHUNK_LEN: usize = 1 << 20;
+
+/// Computes the CRC-64/NVME of `bytes`, using all threads of the current
+/// rayon thread pool.
+///
+/// Each thread is given at least 1 MiB, so buffers of up to 1 MiB are
+/// checksummed on the calling thread.
+///
+/// ```rust
+/// use crc64fast_nvme::par_checksum;
+///
+/// assert_eq!(par_checksum(b"hello world!"), 0xd9160d1fa8e418e3);
+/// ```
+pub fn par_checksum(bytes: &[u8]) -> u64 {
+    let chunk_len = (bytes.len() / rayon::current_num_threads()).max(MIN_CHUNK_LEN);
+    par_checksum_chunks(bytes, chunk_len)
+}
+
+fn par_checksum_chunks(bytes: &[u8], chunk_len: usize) -> u64 {
+    // Runtime CPU feature detection is only performed once.
+    let computer = pclmulqdq::get_update();
+    let (crc, _) = bytes
+        .par_chunks(chunk_len)
+        .map(|chunk| (chunk_checksum(computer, chunk), chunk.len() as u64))
+        .reduce(
+            || (0, 0),
+            |(crc_a, len_a), (crc_b, len_b)| (combine(crc_a, crc_b, len_b), len_a + len_b),
+        );
+    crc
+}
+
+fn chunk_checksum(computer: &Computer, bytes: &[u8]) -> u64 {
+    unsafe { !(computer.update)(!0, bytes) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_large_buffer() {
+        let bytes: Vec<u8> = (0..5 * MIN_CHUNK_LEN + 123)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect();
+        assert_eq!(par_checksum(&bytes), checksum(&bytes));
+        assert_eq!(par_checksum(b""), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn equivalent_to_digest(bytes in any_with::<Vec<u8>>(size_range(..65536).lift()), chunk_len in 1..8192usize) {
+            prop_assert_eq!(par_checksum_chunks(&bytes, chunk_len), checksum(&bytes));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Updating a CRC-64/NVME value in place after some bytes of the data changed.
+//!
+//! CRCs are linear over GF(2): XOR-ing two equally long messages XORs their
+//! CRCs (the initial value and final XOR cancel out). Overwriting `old_bytes`
+//! with `new_bytes` therefore changes the checksum by the CRC of their
+//! difference, shifted past the bytes that follow the patched range.
+
+use super::{combine, table};
+
+/// Recomputes the CRC-64/NVME of a message after `old_bytes` at `offset`
+/// were overwritten with `new_bytes`, without reading the rest of it.
+///
+/// `old_crc` is the checksum of the original `total_len`-byte message.
+///
+/// # Panics
+///
+/// Panics if `old_bytes` and `new_bytes` differ in length, or if the patched
+/// range does not fit inside the message.
+///
+/// ```rust
+/// use crc64fast_nvme::{patch_crc, Digest};
+///
+/// let mut c = Digest::new();
+/// c.write(b"hello world!");
+/// let patched = patch_crc(c.sum64(), 12, 6, b"world", b"there");
+///
+/// let mut c = Digest::new();
+/// c.write(b"hello there!");
+/// assert_eq!(patched, c.sum64());
+/// ```
+pub fn patch_crc(
+    old_crc: u64,
+    total_len: u64,
+    offset: u64,
+    old_bytes: &[u8],
+    new_bytes: &[u8],
+) -> u64 {
+    assert_eq!(
+        old_bytes.len(),
+        new_bytes.len(),
+        "old and new bytes must have the same length"
+    );
+    let len = old_bytes.len() as u64;
+    let end = offset
+        .checked_add(len)
+        .filter(|end| *end <= total_len)
+        .expect("patched range is out of bounds");
+
+    // CRC of the difference, with a zero initial value and no final XOR.
+    let mut delta = 0;
+    let mut buf = [0u8; 256];
+    for (old, new) in old_bytes.chunks(buf.len()).zip(new_bytes.chunks(buf.len())) {
+        for ((d, o), n) in buf.iter_mut().zip(old).zip(new) {
+            *d = o ^ n;
+        }
+        delta = table::update(delta, &buf[..old.len()]);
+    }
+
+    old_crc ^ combine::shift(delta, total_len - end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::{size_range, vec};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_unchanged() {
+        let crc = checksum(b"123456789");
+        assert_eq!(patch_crc(crc, 9, 3, b"456", b"456"), crc);
+        assert_eq!(patch_crc(crc, 9, 9, b"", b""), crc);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_out_of_bounds() {
+        patch_crc(0, 9, 8, b"89", b"00");
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_length_mismatch() {
+        patch_crc(0, 9, 0, b"12", b"123");
+    }
+
+    fn bytes_and_patch() -> impl Strategy<Value = (Vec<u8>, usize, Vec<u8>)> {
+        any_with::<Vec<u8>>(size_range(1..16384).lift())
+            .prop_flat_map(|bytes| (0..bytes.len(), Just(bytes)))
+            .prop_flat_map(|(offset, bytes)| {
+                let patch = vec(any::<u8>(), 0..=bytes.len() - offset);
+                (Just(bytes), Just(offset), patch)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn patch_equivalent_to_rehash((bytes, offset, patch) in bytes_and_patch()) {
+            let mut patched = bytes.clone();
+            patched[offset..offset + patch.len()].copy_from_slice(&patch);
+
+            prop_assert_eq!(
+                patch_crc(
+                    checksum(&bytes),
+                    bytes.len() as u64,
+                    offset as u64,
+                    &bytes[offset..offset + patch.len()],
+                    &patch,
+                ),
+                checksum(&patched),
+            );
+        }
+    }
+}
@@ -2,8 +2,10 @@
 
 //! AArch64 implementation of the PCLMULQDQ-based CRC calculation.
 
-use std::arch::{aarch64::*, is_aarch64_feature_detected};
-use std::ops::BitXor;
+use core::arch::aarch64::*;
+use core::ops::BitXor;
+#[cfg(feature = "std")]
+use std::arch::is_aarch64_feature_detected;
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug)]
@@ -43,10 +45,16 @@ impl Simd {
 }
 
 impl super::SimdExt for Simd {
+    #[cfg(feature = "std")]
     fn is_supported() -> bool {
         is_aarch64_feature_detected!("pmull") && is_aarch64_feature_detected!("neon")
     }
 
+    #[cfg(not(feature = "std"))]
+    fn is_supported() -> bool {
+        cfg!(all(target_feature = "aes", target_feature = "neon"))
+    }
+
     #[inline]
     #[target_feature(enable = "neon")]
     unsafe fn new(high: u64, low: u64) -> Self {
@@ -7,7 +7,7 @@
 //! 100× slower than a real SIMD implementation, and should never be used in
 //! production code.
 
-use std::ops::BitXor;
+use core::ops::BitXor;
 
 #[repr(align(16))]
 #[derive(Copy, Clone, Debug)]
@@ -7,12 +7,12 @@
 //!
 //! [white paper]: https://web.archive.org/web/20131224125630/https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf
 
-use std::{
+use core::{
     fmt::Debug,
     ops::{BitXor, BitXorAssign},
 };
 
-use super::table;
+use super::{table, Computer, UpdateFn};
 
 use self::arch::Simd;
 
@@ -29,6 +29,9 @@ mod arch;
 /// platform-specific SIMD implementations.
 trait SimdExt: Copy + Debug + BitXor {
     /// Returns whether SIMD-accelerated carryless multiplication is supported.
+    ///
+    /// Without the `std` feature, this can only check the target features
+    /// enabled at compile time.
     fn is_supported() -> bool;
 
     /// Creates a new 128-bit integer from the 64-bit parts.
@@ -56,7 +59,7 @@ trait SimdExt: Copy + Debug + BitXor {
 impl PartialEq for Simd {
     fn eq(&self, other: &Self) -> bool {
         unsafe {
-            use std::mem::transmute;
+            use core::mem::transmute;
             let a: u128 = transmute(*self);
             let b: u128 = transmute(*other);
             a == b
@@ -72,19 +75,34 @@ impl BitXorAssign for Simd {
     }
 }
 
-pub fn get_update() -> super::UpdateFn {
+/// The 128-bit SIMD algorithm.
+const SIMD_128: &Computer = &Computer {
+    update: update_128_batch,
+    update_vectored: update_128_vectored,
+    update_many: update_128_many,
+};
+
+/// The 256-bit SIMD algorithm.
+#[cfg(feature = "vpclmulqdq")]
+const SIMD_256: &Computer = &Computer {
+    update: update_256_batch,
+    update_vectored: update_128_vectored,
+    update_many: update_256_many,
+};
+
+pub(crate) fn get_update() -> &'static Computer {
     #[cfg(feature = "vpclmulqdq")]
     {
         use arch::vpclmulqdq::*;
         if Simd256::is_supported() {
-            return update_256_batch;
+            return SIMD_256;
         }
     }
 
     if Simd::is_supported() {
-        update_128_batch
+        SIMD_128
     } else {
-        table::update
+        table::COMPUTER
     }
 }
 
@@ -140,6 +158,170 @@ unsafe fn update_simd(state: u64, first: &[Simd; 8], rest: &[[Simd; 8]]) -> u64
     fold_tail(x)
 }
 
+// This function is unsafe because it uses platform dependent functions.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_128_vectored(state: u64, bufs: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+    const BLOCK: usize = core::mem::size_of::<[Simd; 8]>();
+
+    // The fold state is kept across buffers; blocks straddling two buffers
+    // are assembled in `pending`, and only the final partial block is left
+    // to the table-based algorithm.
+    let mut x: Option<[Simd; 8]> = None;
+    let mut pending = [0u8; BLOCK];
+    let mut pending_len = 0;
+    let coeff = Simd::new(table::K_1023, table::K_1087);
+
+    let mut absorb = |block: *const u8| {
+        let block = block.cast::<[Simd; 8]>().read_unaligned();
+        match &mut x {
+            Some(x) => {
+                for (xi, yi) in x.iter_mut().zip(block.iter()) {
+                    *xi = *yi ^ xi.fold_16(coeff);
+                }
+            }
+            None => {
+                let mut block = block;
+                block[0] ^= Simd::new(0, state);
+                x = Some(block);
+            }
+        }
+    };
+
+    for mut bytes in bufs {
+        if pending_len > 0 {
+            let n = bytes.len().min(BLOCK - pending_len);
+            pending[pending_len..pending_len + n].copy_from_slice(&bytes[..n]);
+            pending_len += n;
+            bytes = &bytes[n..];
+            if pending_len < BLOCK {
+                continue;
+            }
+            absorb(pending.as_ptr());
+        }
+        let mut chunks = bytes.chunks_exact(BLOCK);
+        for chunk in &mut chunks {
+            absorb(chunk.as_ptr());
+        }
+        let rest = chunks.remainder();
+        pending[..rest.len()].copy_from_slice(rest);
+        pending_len = rest.len();
+    }
+
+    let state = match x {
+        Some(x) => fold_tail(x),
+        None => state,
+    };
+    table::update(state, &pending[..pending_len])
+}
+
+/// The number of independent messages folded together by `update_128_many`
+/// and `update_256_many`, so that the carryless multiplications of one do not
+/// wait on the results of the previous ones.
+const LANES: usize = 8;
+
+/// Groups with a message at least this long are checksummed one by one: the
+/// fixed cost of a message is already small next to its length, and reading
+/// `LANES` long messages at once is slower than reading them one after
+/// another.
+const MAX_LANE_LEN: usize = 2048;
+
+/// Folds the given number of 16-byte blocks of each lane into its
+/// accumulator, advancing the block pointers.
+type FoldLanesFn = unsafe fn(&mut [Simd; LANES], &mut [*const u8; LANES], usize);
+
+/// Reduces a single 16-byte fold accumulator to the CRC register.
+#[inline(always)]
+unsafe fn reduce_lane(x: Simd) -> u64 {
+    x.fold_8(table::K_127) // fold 16 bytes into 8 bytes.
+        .barrett(table::POLY, table::MU) // barrett reduction.
+}
+
+// This function is unsafe because it uses platform dependent functions.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_128_many(states: &mut [u64], inputs: &[&[u8]]) {
+    update_many(states, inputs, fold_128_lanes, update_128_batch)
+}
+
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn fold_128_lanes(x: &mut [Simd; LANES], blocks: &mut [*const u8; LANES], rounds: usize) {
+    let coeff = Simd::new(table::K_127, table::K_191);
+    let mut acc = *x;
+    for k in 0..rounds {
+        for (acc, block) in acc.iter_mut().zip(&*blocks) {
+            *acc = block.add(16 * k).cast::<Simd>().read_unaligned() ^ acc.fold_16(coeff);
+        }
+    }
+    *x = acc;
+    for block in blocks {
+        *block = block.add(16 * rounds);
+    }
+}
+
+/// Folds each group of `LANES` messages in lockstep with `fold`, for as long
+/// as every one of them has blocks left, then reduces each lane and finishes
+/// its message with `update`. Groups with an empty lane are checksummed one
+/// by one.
+#[inline(always)]
+unsafe fn update_many(states: &mut [u64], inputs: &[&[u8]], fold: FoldLanesFn, update: UpdateFn) {
+    let mut states = states.chunks_exact_mut(LANES);
+    let mut inputs = inputs.chunks_exact(LANES);
+    for (states, inputs) in (&mut states).zip(&mut inputs) {
+        let rounds = inputs
+            .iter()
+            .map(|bytes| bytes.len() / 16)
+            .min()
+            .unwrap_or(0);
+        if rounds == 0 || inputs.iter().any(|bytes| bytes.len() >= MAX_LANE_LEN) {
+            for (state, bytes) in states.iter_mut().zip(inputs) {
+                *state = update(*state, bytes);
+            }
+            continue;
+        }
+
+        let mut x = [Simd::new(0, 0); LANES];
+        let mut blocks = [core::ptr::null(); LANES];
+        for (i, bytes) in inputs.iter().enumerate() {
+            x[i] = bytes.as_ptr().cast::<Simd>().read_unaligned() ^ Simd::new(0, states[i]);
+            blocks[i] = bytes.as_ptr().add(16);
+        }
+        fold(&mut x, &mut blocks, rounds - 1);
+        for (i, bytes) in inputs.iter().enumerate() {
+            states[i] = update(reduce_lane(x[i]), &bytes[16 * rounds..]);
+        }
+    }
+    for (state, bytes) in states.into_remainder().iter_mut().zip(inputs.remainder()) {
+        *state = update(*state, bytes);
+    }
+}
+
+#[cfg(feature = "vpclmulqdq")]
+#[target_feature(enable = "avx2", enable = "vpclmulqdq")]
+unsafe fn update_256_many(states: &mut [u64], inputs: &[&[u8]]) {
+    use arch::vpclmulqdq::*;
+    update_many(states, inputs, fold_vpclmulqdq_lanes, update_256_batch)
+}
+
 #[inline(always)]
 unsafe fn fold_tail(x: [Simd; 8]) -> u64 {
     let coeffs = [
@@ -3,20 +3,30 @@
 //! x86 (32-bit) implementation of the PCLMULQDQ-based CRC calculation.
 
 #[cfg(target_arch = "x86")]
-use std::arch::x86::*;
-use std::ops::BitXor;
+use core::arch::x86::*;
+use core::ops::BitXor;
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug)]
 pub struct Simd(__m128i);
 
 impl super::SimdExt for Simd {
+    #[cfg(feature = "std")]
     fn is_supported() -> bool {
         is_x86_feature_detected!("pclmulqdq") // _mm_clmulepi64_si128
             && is_x86_feature_detected!("sse2") // (all other _mm_*)
             && is_x86_feature_detected!("sse4.1")
     }
 
+    #[cfg(not(feature = "std"))]
+    fn is_supported() -> bool {
+        cfg!(all(
+            target_feature = "pclmulqdq",
+            target_feature = "sse2",
+            target_feature = "sse4.1"
+        ))
+    }
+
     #[inline]
     #[target_feature(enable = "sse2")]
     unsafe fn new(high: u64, low: u64) -> Self {
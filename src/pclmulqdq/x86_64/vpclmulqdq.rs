@@ -1,12 +1,15 @@
 use super::{super::fold_tail, Simd, __cpuid_count, __m256i, _mm256_set_epi64x, _mm256_xor_si256};
+use super::super::LANES;
+use core::arch::x86_64::{_mm256_clmulepi64_epi128, _mm256_loadu2_m128i};
 use core::ops::BitXor;
+#[cfg(feature = "std")]
 use lazy_static::lazy_static;
-use std::arch::x86_64::_mm256_clmulepi64_epi128;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Simd256(__m256i);
 
 // this lazy_static bit takes throughput from ~39GiB/s to ~52GiB/s
+#[cfg(feature = "std")]
 lazy_static! {
     static ref VPCLMULQDQ_SUPPORTED : bool = {
         let avx2 = is_x86_feature_detected!("avx2");
@@ -19,11 +22,18 @@ lazy_static! {
 }
 
 impl Simd256 {
+    #[cfg(feature = "std")]
     #[inline]
     pub fn is_supported() -> bool {
         *VPCLMULQDQ_SUPPORTED
     }
 
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    pub fn is_supported() -> bool {
+        cfg!(all(target_feature = "avx2", target_feature = "vpclmulqdq"))
+    }
+
     #[inline]
     #[target_feature(enable = "avx2")]
     pub unsafe fn new(x3: u64, x2: u64, x1: u64, x0: u64) -> Self {
@@ -36,6 +46,14 @@ impl Simd256 {
         core::mem::transmute(self4)
     }
 
+    /// Reads 16 bytes at `low` and 16 bytes at `high` into the low and high
+    /// halves.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn load_pair(low: *const u8, high: *const u8) -> Self {
+        Self(_mm256_loadu2_m128i(high.cast(), low.cast()))
+    }
+
     #[inline]
     #[target_feature(enable = "avx2", enable = "vpclmulqdq")]
     pub unsafe fn fold_32(self, coeff: Self) -> Self {
@@ -96,6 +114,35 @@ pub(crate) unsafe fn update_vpclmulqdq(
     fold_tail(x)
 }
 
+/// Folds the given number of 16-byte blocks of each lane into its
+/// accumulator, two lanes per register, advancing the block pointers.
+#[target_feature(enable = "avx2", enable = "vpclmulqdq")]
+pub(crate) unsafe fn fold_vpclmulqdq_lanes(
+    x: &mut [Simd; LANES],
+    blocks: &mut [*const u8; LANES],
+    rounds: usize,
+) {
+    let coeff = Simd256::new(
+        crate::table::K_127,
+        crate::table::K_191,
+        crate::table::K_127,
+        crate::table::K_191,
+    );
+
+    // pair up the lanes, each register holding two of them
+    let mut acc: [Simd256; LANES / 2] = core::mem::transmute(*x);
+    for k in 0..rounds {
+        for (acc, pair) in acc.iter_mut().zip(blocks.chunks_exact(2)) {
+            *acc =
+                Simd256::load_pair(pair[0].add(16 * k), pair[1].add(16 * k)) ^ acc.fold_32(coeff);
+        }
+    }
+    *x = core::mem::transmute::<[Simd256; LANES / 2], [Simd; LANES]>(acc);
+    for block in blocks {
+        *block = block.add(16 * rounds);
+    }
+}
+
 impl PartialEq for Simd256 {
     fn eq(&self, other: &Self) -> bool {
         unsafe {
@@ -0,0 +1,145 @@
+//! The CRC-64/NVME of a fixed-size window sliding over a byte stream.
+//!
+//! Moving the window one byte forward feeds the incoming byte into the
+//! register as usual, and cancels the contribution of the outgoing byte with
+//! a table lookup, since the outgoing byte is always at the same distance
+//! from the end.
+//!
+//! The register after a message `M` of `n` bytes, starting from `init`, is
+//! `init·x⁸ⁿ ⊕ R(M)`, where `R` is linear in the bytes, and a byte `b` followed
+//! by `k` bytes contributes `TABLE_0[b]·x⁸ᵏ` to `R`. After feeding `in_byte`
+//! into the register of the window `out_byte ‖ W`, the register differs from
+//! that of `W ‖ in_byte` by:
+//!
+//! ```text
+//! TABLE_0[out_byte]·x⁸ʷ ⊕ !0·(x⁸ʷ⁺⁸ ⊕ x⁸ʷ)
+//! ```
+//!
+//! where `w` is the window size: the contribution of the outgoing byte, and
+//! the initial register moving one byte closer to the end. This only depends
+//! on `out_byte` for a given window size, so it is precomputed for each byte.
+
+use super::{
+    combine::{multiply, shift, x8n},
+    pclmulqdq, table, Computer,
+};
+
+/// Computes the CRC-64/NVME of a window of fixed size which slides over a
+/// byte stream, in O(1) per byte, e.g. to find chunk boundaries or to
+/// resynchronize with known blocks.
+///
+/// The first window is written with [`write`](Self::write), after which
+/// [`roll`](Self::roll) moves it forward one byte at a time.
+///
+/// ```rust
+/// use crc64fast_nvme::{Digest, RollingDigest};
+///
+/// let data = b"hello world!";
+/// let mut r = RollingDigest::new(6);
+/// r.write(&data[..6]);
+/// for i in 6..data.len() {
+///     r.roll(data[i - 6], data[i]);
+/// }
+///
+/// let mut c = Digest::new();
+/// c.write(b"world!");
+/// assert_eq!(r.sum64(), c.sum64());
+/// ```
+#[derive(Clone)]
+pub struct RollingDigest {
+    computer: &'static Computer,
+    window: usize,
+    state: u64,
+    // The value cancelling each outgoing byte, see the module documentation.
+    outgoing: [u64; 256],
+}
+
+impl RollingDigest {
+    /// Creates a new `RollingDigest` over windows of `window` bytes.
+    ///
+    /// This precomputes a 2 KiB table for the window size, and performs
+    /// runtime CPU feature detection for [`write`](Self::write).
+    pub fn new(window: usize) -> Self {
+        // x⁸ʷ
+        let window_shift = x8n(window as u64);
+        // !0·(x⁸ʷ⁺⁸ ⊕ x⁸ʷ)
+        let init_delta = multiply(window_shift, shift(!0, 1) ^ !0);
+        let mut outgoing = [0; 256];
+        for (entry, value) in outgoing.iter_mut().zip(&table::TABLE_0) {
+            *entry = multiply(window_shift, *value) ^ init_delta;
+        }
+        Self {
+            computer: pclmulqdq::get_update(),
+            window,
+            state: !0,
+            outgoing,
+        }
+    }
+
+    /// Returns the size of the window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Writes data into the window, before rolling starts.
+    ///
+    /// Exactly `window` bytes must be written before the first call to
+    /// [`roll`](Self::roll).
+    pub fn write(&mut self, bytes: &[u8]) {
+        unsafe {
+            self.state = (self.computer.update)(self.state, bytes);
+        }
+    }
+
+    /// Slides the window one byte forward, removing `out_byte` from its start
+    /// and appending `in_byte` to its end.
+    #[inline]
+    pub fn roll(&mut self, out_byte: u8, in_byte: u8) {
+        self.state = table::update_1(self.state, in_byte) ^ self.outgoing[usize::from(out_byte)];
+    }
+
+    /// Returns the CRC-64/NVME of the current window.
+    pub fn sum64(&self) -> u64 {
+        !self.state
+    }
+
+    /// Empties the window, keeping its size.
+    pub fn reset(&mut self) {
+        self.state = !0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::checksum;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_reset() {
+        let mut r = RollingDigest::new(4);
+        r.write(b"abcd");
+        r.roll(b'a', b'e');
+        r.reset();
+        r.write(b"wxyz");
+        assert_eq!(r.window(), 4);
+        assert_eq!(r.sum64(), checksum(b"wxyz"));
+        r.roll(b'w', b'!');
+        assert_eq!(r.sum64(), checksum(b"xyz!"));
+    }
+
+    proptest! {
+        #[test]
+        fn equivalent_to_recomputing(bytes in any_with::<Vec<u8>>(size_range(1..2048).lift()), window in any::<prop::sample::Index>()) {
+            let window = window.index(bytes.len()) + 1;
+            let mut r = RollingDigest::new(window);
+            r.write(&bytes[..window]);
+            prop_assert_eq!(r.sum64(), checksum(&bytes[..window]));
+            for end in window..bytes.len() {
+                r.roll(bytes[end - window], bytes[end]);
+                prop_assert_eq!(r.sum64(), checksum(&bytes[end + 1 - window..=end]));
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! [RustCrypto `digest`](https://docs.rs/digest) trait implementations, so
+//! that [`Digest`] can be used wherever a `digest::Digest` is expected.
+//!
+//! Enable the `digest` feature to use these. The output is the 8-byte
+//! big-endian CRC-64/NVME value.
+
+use digest::{
+    consts::U8, FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update,
+};
+
+use super::Digest;
+
+impl HashMarker for Digest {}
+
+impl OutputSizeUser for Digest {
+    type OutputSize = U8;
+}
+
+impl Update for Digest {
+    fn update(&mut self, data: &[u8]) {
+        self.write(data);
+    }
+}
+
+impl FixedOutput for Digest {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.sum64().to_be_bytes());
+    }
+}
+
+impl Reset for Digest {
+    fn reset(&mut self) {
+        Digest::reset(self);
+    }
+}
+
+impl FixedOutputReset for Digest {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.sum64().to_be_bytes());
+        Digest::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    fn generic_digest<D: digest::Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = D::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    fn generic_dyn_digest(hasher: &mut dyn digest::DynDigest, data: &[u8]) -> [u8; 8] {
+        let mut out = [0; 8];
+        hasher.update(data);
+        hasher.finalize_into_reset(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_standard_vector() {
+        let output = <Digest as digest::Digest>::digest(b"123456789");
+        assert_eq!(output[..], 0xae8b14860a799888u64.to_be_bytes());
+        assert_eq!(<Digest as digest::Digest>::output_size(), 8);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut hasher = Digest::new();
+        let first = generic_dyn_digest(&mut hasher, b"hello world!");
+        let second = generic_dyn_digest(&mut hasher, b"hello world!");
+        assert_eq!(first, 0xd9160d1fa8e418e3u64.to_be_bytes());
+        assert_eq!(first, second);
+    }
+
+    proptest! {
+        #[test]
+        fn equivalent_to_sum64(left in any_with::<Vec<u8>>(size_range(..8192).lift()), right in any_with::<Vec<u8>>(size_range(..8192).lift())) {
+            let mut hasher = Digest::new();
+            hasher.write(&left);
+            hasher.write(&right);
+            prop_assert_eq!(
+                generic_digest::<Digest>(&[&left, &right]),
+                hasher.sum64().to_be_bytes().to_vec()
+            );
+        }
+    }
+}
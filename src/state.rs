@@ -0,0 +1,147 @@
+//! Saving and restoring the state of an in-progress [`Digest`].
+//!
+//! Only the raw CRC register and the number of bytes written are stored. The
+//! algorithm is selected again, by runtime CPU feature detection, on restore,
+//! so a state saved on one machine can be resumed on another.
+//!
+//! The byte format is stable: a 4-byte header (`b"C64"` and a version byte of
+//! `1`), followed by the register and the byte count as little-endian `u64`s.
+
+use super::{pclmulqdq, Digest};
+
+/// The length of a serialized [`Digest`] state, in bytes.
+pub const DIGEST_STATE_LEN: usize = 20;
+
+const HEADER: [u8; 4] = *b"C64\x01";
+
+impl Digest {
+    /// Serializes the state of the digest, to be resumed with
+    /// [`from_bytes`](Self::from_bytes).
+    ///
+    /// ```rust
+    /// use crc64fast_nvme::Digest;
+    ///
+    /// let mut c = Digest::new();
+    /// c.write(b"hello ");
+    /// let saved = c.to_bytes();
+    ///
+    /// let mut c = Digest::from_bytes(&saved).unwrap();
+    /// c.write(b"world!");
+    /// assert_eq!(c.sum64(), 0xd9160d1fa8e418e3);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; DIGEST_STATE_LEN] {
+        let mut bytes = [0; DIGEST_STATE_LEN];
+        bytes[..4].copy_from_slice(&HEADER);
+        bytes[4..12].copy_from_slice(&self.state.to_le_bytes());
+        bytes[12..].copy_from_slice(&self.amount.to_le_bytes());
+        bytes
+    }
+
+    /// Restores a digest serialized with [`to_bytes`](Self::to_bytes).
+    ///
+    /// Like [`new`](Self::new), it performs runtime CPU feature detection.
+    /// Returns `None` if `bytes` is not a serialized digest state.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != DIGEST_STATE_LEN || bytes[..4] != HEADER {
+            return None;
+        }
+        let state = u64::from_le_bytes(bytes[4..12].try_into().unwrap());
+        let amount = u64::from_le_bytes(bytes[12..].try_into().unwrap());
+        Some(Self {
+            computer: pclmulqdq::get_update(),
+            state,
+            amount,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::super::{pclmulqdq, Digest};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Digest")]
+    struct DigestState {
+        state: u64,
+        amount: u64,
+    }
+
+    impl Serialize for Digest {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            DigestState {
+                state: self.state,
+                amount: self.amount,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Digest {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let DigestState { state, amount } = DigestState::deserialize(deserializer)?;
+            Ok(Self {
+                computer: pclmulqdq::get_update(),
+                state,
+                amount,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_format() {
+        let mut digest = Digest::new();
+        digest.write(b"123456789");
+        let bytes = digest.to_bytes();
+        assert_eq!(&bytes[..4], b"C64\x01");
+        assert_eq!(bytes[4..12], (!0xae8b14860a799888u64).to_le_bytes());
+        assert_eq!(bytes[12..], 9u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let bytes = Digest::new().to_bytes();
+        assert!(Digest::from_bytes(&bytes[..19]).is_none());
+        assert!(Digest::from_bytes(&[0; DIGEST_STATE_LEN]).is_none());
+        let mut future = bytes;
+        future[3] = 2;
+        assert!(Digest::from_bytes(&future).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut digest = Digest::new();
+        digest.write(b"hello ");
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(
+            json,
+            format!(r#"{{"state":{},"amount":6}}"#, digest.state())
+        );
+
+        let mut restored: Digest = serde_json::from_str(&json).unwrap();
+        restored.write(b"world!");
+        assert_eq!(restored.sum64(), 0xd9160d1fa8e418e3);
+    }
+
+    proptest! {
+        #[test]
+        fn resume(left in any_with::<Vec<u8>>(size_range(..8192).lift()), right in any_with::<Vec<u8>>(size_range(..8192).lift())) {
+            let mut hasher_1 = Digest::new();
+            hasher_1.write(&left);
+            let mut hasher_2 = Digest::from_bytes(&hasher_1.to_bytes()).unwrap();
+            hasher_1.write(&right);
+            hasher_2.write(&right);
+            prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
+            prop_assert_eq!(hasher_1.to_bytes(), hasher_2.to_bytes());
+        }
+    }
+}
@@ -2,33 +2,91 @@
 
 //! Table-based CRC-64/NVME computer.
 //!
-//! The update function computes the CRC value 16 bytes at a time.
-//! The processing speed is roughly 5× of the one-byte-at-a-time method.
+//! By default, the update function computes the CRC value 16 bytes at a time
+//! (slice-by-16), which is roughly 5× the speed of the one-byte-at-a-time
+//! method, using sixteen 2 KiB tables. The `slice-by-8`, `slice-by-4` and
+//! `byte-at-a-time` features trade speed for smaller tables (16 KiB, 8 KiB
+//! and 2 KiB). If several are enabled, the smallest one wins.
 
 #![allow(clippy::unreadable_literal)]
 
+use super::Computer;
+
 pub(crate) fn update(mut state: u64, bytes: &[u8]) -> u64 {
-    // Safe.
-    let (left, middle, right) = unsafe { bytes.align_to::<[u8; 16]>() };
-    for b in left {
-        state = update_1(state, *b);
+    #[cfg(feature = "byte-at-a-time")]
+    {
+        state = bytes.iter().fold(state, |s, &b| update_1(s, b));
+    }
+    #[cfg(not(feature = "byte-at-a-time"))]
+    {
+        // Safe.
+        let (left, middle, right) = unsafe { bytes.align_to::<Block>() };
+        for b in left {
+            state = update_1(state, *b);
+        }
+        for b in middle {
+            state = update_block(state, b);
+        }
+        for b in right {
+            state = update_1(state, *b);
+        }
     }
-    for b in middle {
-        state = update_16(state, b);
+    state
+}
+
+/// Performs the CRC-64/NVME update over several buffers.
+pub(crate) fn update_vectored(mut state: u64, bufs: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+    for bytes in bufs {
+        state = update(state, bytes);
     }
-    for b in right {
-        state = update_1(state, *b);
+    state
+}
+
+/// Performs the CRC-64/NVME update of several independent states, each over
+/// its own buffer.
+pub(crate) fn update_many(states: &mut [u64], inputs: &[&[u8]]) {
+    for (state, bytes) in states.iter_mut().zip(inputs) {
+        *state = update(*state, bytes);
+    }
+}
+
+/// The table-based algorithm. A `const` rather than a `static`, so that
+/// `Digest::new_table` can refer to it.
+pub(crate) const COMPUTER: &Computer = &Computer {
+    update,
+    update_vectored,
+    update_many,
+};
+
+/// Performs the CRC-64/NVME update, one byte at a time, in a const context.
+pub(crate) const fn update_const(mut state: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        state = (state >> 8) ^ TABLE_0_CONST[(bytes[i] ^ (state as u8)) as usize];
+        i += 1;
     }
     state
 }
 
 /// Performs the CRC-64/NVME update, one byte at a time.
-fn update_1(state: u64, b: u8) -> u64 {
+pub(crate) fn update_1(state: u64, b: u8) -> u64 {
     (state >> 8) ^ TABLE_0[usize::from(b ^ (state as u8))]
 }
 
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
+type Block = [u8; 16];
+
 /// Performs the CRC-64/NVME update, 16 bytes at a time.
-fn update_16(state: u64, b: &[u8; 16]) -> u64 {
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
+fn update_block(state: u64, b: &[u8; 16]) -> u64 {
     let s = state.to_le_bytes();
     TABLE_0[usize::from(b[15])]
         ^ TABLE_1[usize::from(b[14])]
@@ -48,6 +106,69 @@ fn update_16(state: u64, b: &[u8; 16]) -> u64 {
         ^ TABLE_15[usize::from(b[0] ^ s[0])]
 }
 
+#[cfg(all(
+    feature = "slice-by-8",
+    not(any(feature = "slice-by-4", feature = "byte-at-a-time"))
+))]
+type Block = [u8; 8];
+
+/// Performs the CRC-64/NVME update, 8 bytes at a time.
+#[cfg(all(
+    feature = "slice-by-8",
+    not(any(feature = "slice-by-4", feature = "byte-at-a-time"))
+))]
+fn update_block(state: u64, b: &[u8; 8]) -> u64 {
+    let s = state.to_le_bytes();
+    TABLE_0[usize::from(b[7] ^ s[7])]
+        ^ TABLE_1[usize::from(b[6] ^ s[6])]
+        ^ TABLE_2[usize::from(b[5] ^ s[5])]
+        ^ TABLE_3[usize::from(b[4] ^ s[4])]
+        ^ TABLE_4[usize::from(b[3] ^ s[3])]
+        ^ TABLE_5[usize::from(b[2] ^ s[2])]
+        ^ TABLE_6[usize::from(b[1] ^ s[1])]
+        ^ TABLE_7[usize::from(b[0] ^ s[0])]
+}
+
+#[cfg(all(feature = "slice-by-4", not(feature = "byte-at-a-time")))]
+type Block = [u8; 4];
+
+/// Performs the CRC-64/NVME update, 4 bytes at a time.
+#[cfg(all(feature = "slice-by-4", not(feature = "byte-at-a-time")))]
+fn update_block(state: u64, b: &[u8; 4]) -> u64 {
+    let s = state.to_le_bytes();
+    (state >> 32)
+        ^ TABLE_0[usize::from(b[3] ^ s[3])]
+        ^ TABLE_1[usize::from(b[2] ^ s[2])]
+        ^ TABLE_2[usize::from(b[1] ^ s[1])]
+        ^ TABLE_3[usize::from(b[0] ^ s[0])]
+}
+
+/// Runs the CRC-64/NVME register backwards over `bytes`, undoing an update.
+///
+/// Given the raw register `state` after `bytes` were written (the bitwise NOT
+/// of the CRC-64/NVME value), returns the register before they were written,
+/// so that `update(unupdate(state, bytes), bytes) == state`.
+///
+/// ```rust
+/// use crc64fast_nvme::{unupdate, Digest};
+///
+/// let mut c = Digest::new();
+/// c.write(b"hello world!");
+///
+/// // recover the register after `hello ` from the final CRC.
+/// let state = unupdate(!c.sum64(), b"world!");
+///
+/// let mut c = Digest::new();
+/// c.write(b"hello ");
+/// assert_eq!(!state, c.sum64());
+/// ```
+pub fn unupdate(mut state: u64, bytes: &[u8]) -> u64 {
+    for b in bytes.iter().rev() {
+        state = (state << 8) ^ TABLE_REV[(state >> 56) as usize] ^ u64::from(*b);
+    }
+    state
+}
+
 // Constants used in SIMD-based computations.
 // All K_nnn constants are computed by bit_reverse(x^nnn mod POLY).
 // Used the NVME polynomial (0xAD93D23594C93659) from the
@@ -71,15 +192,169 @@ pub(crate) const K_1087: u64 = 0xa1ca_681e_733f_9c40;
 /// The reciprocal of the NVME polynomial (0xAD93D23594C93659)
 pub(crate) const POLY: u64 = 0x34d9_2653_5897_936b;
 
+/// The bit-reversed NVME polynomial (0xAD93D23594C93659), without the x⁶⁴ term.
+pub(crate) const POLY_REFLECTED: u64 = 0x9a6c_9329_ac4b_c9b5;
+
 /// The polynomial quotient x¹²⁸/POLY.
 pub(crate) const MU: u64 = 0x27ec_fa32_9aef_9f77;
 
+/// The register value after processing any data followed by its
+/// little-endian CRC-64/NVME.
+pub(crate) const RESIDUE: u64 = 0xf310_303b_2b6f_6e42;
+
+// Powers of x used to shift a CRC register past runs of zero bytes.
+// Generated by running `./build_table x2n`.
+//
+// `X2N_TABLE[k]` is x^(2^(k+3)) mod POLY (i.e. the shift by 2ᵏ bytes), bit-reversed.
+pub(crate) static X2N_TABLE: [u64; 64] = [
+    0x0080000000000000,
+    0x0000800000000000,
+    0x0000000080000000,
+    0x9a6c9329ac4bc9b5,
+    0x10f4bb0f129310d6,
+    0x70f05dcea2ebd226,
+    0x311211205672822d,
+    0x2fc297db0f46c96e,
+    0xca4d536fabf7da84,
+    0xfb4cdc3b379ee6ed,
+    0xea261148df25140a,
+    0x59ccb2c07aa6c9b4,
+    0x20b3674a839af27a,
+    0x2d8e1986da94d583,
+    0x42cdf4c20337635d,
+    0x1d78724bf0f26839,
+    0xb96c84e0afb34bd5,
+    0x5d2e1fcd2df0a3ea,
+    0xcd9506572332be42,
+    0x23bda2427f7d690f,
+    0x347a953232374f07,
+    0x1c2a807ac2a8ceea,
+    0x9b92ad0e14fe1460,
+    0x2574114889f670b2,
+    0x4a84a6c45e3bf520,
+    0x915bbac21cd1c7ff,
+    0xb0290ec579f291f5,
+    0xcf2548505c624e6e,
+    0xb154f27bf08a8207,
+    0xce4e92344baf7d35,
+    0x51da8d7e057c5eb3,
+    0x9fb10823f5be15df,
+    0x73b825b3ff1f71cf,
+    0x5db436c5406ebb74,
+    0xfa7ed8f3ec3f2bca,
+    0xc4d58efdc61b9ef6,
+    0xa7e39e61e855bd45,
+    0x97ad46f9dd1bf2f1,
+    0x1a0abb01f853ee6b,
+    0x3f0827c3348f8215,
+    0x4eb68c4506134607,
+    0x4a46f6de5df34e0a,
+    0x2d855d6a1c57a8dd,
+    0x8688da58e1115812,
+    0x5232f417fc7c7300,
+    0xa4080fb2e767d8da,
+    0xd515a7e17693e562,
+    0x1181f7c862e94226,
+    0x9e23cd058204ca91,
+    0x9b8992c57a0aed82,
+    0xb2c0afb84609b6ff,
+    0x2f7160553a5ea018,
+    0x3cd378b5c99f2722,
+    0x814054ad61a3b058,
+    0xbf766189fce806d8,
+    0x85a5e898ac49f86f,
+    0x34830d11bc84f346,
+    0x9644d95b173c8c1c,
+    0x150401ac9ac759b1,
+    0xebe1f7f46fb00eba,
+    0x8ee4ce0c2e2bd662,
+    0x4000000000000000,
+    0x2000000000000000,
+    0x0800000000000000,
+];
+
+// Inverse powers of x used to shift a CRC register back over runs of zero bytes.
+// Generated by running `./build_table x2n-inv`.
+//
+// `X2N_INV_TABLE[k]` is x^(-2^(k+3)) mod POLY, bit-reversed.
+pub(crate) static X2N_INV_TABLE: [u64; 64] = [
+    0x87c87e03060c1868,
+    0xc1b98ebd81f28879,
+    0x9ef7ddb45244a06c,
+    0xfb044f855b25ffb9,
+    0x3abdf378ace3b178,
+    0x546a8c0a9ee87365,
+    0x1c197c24ca623fd2,
+    0xe72b29c5faead7ac,
+    0x2f8e536c41adb96f,
+    0xb462cd9a549a7382,
+    0x0611ef8ec2ce670e,
+    0xdcbddb354783291b,
+    0x75976367bfb0e2aa,
+    0x945d084fd34672f0,
+    0x9d4156415de46f55,
+    0xb60ff72ab421c4fa,
+    0x282f09b747bbaba2,
+    0xe8718967337a5096,
+    0x9ea77ea476f68ae8,
+    0xcf8689994d3768e9,
+    0xdd4907194508985a,
+    0x48d16fbfbdd5d0fb,
+    0xae4a4db503de61f8,
+    0xc77b7bba5994f892,
+    0x163274dab5edfb6a,
+    0xa7cd51af7fe62c27,
+    0x5318ef8b16a2a683,
+    0xebd0488b419978a4,
+    0x6c56bcd96087c1d2,
+    0xf9222ceaeaaa975f,
+    0x449d2e4ad6e40fc4,
+    0xd291f9ab60bbd88b,
+    0x9efa52352c887b90,
+    0xe7c4803209a737ae,
+    0x98604171272d209b,
+    0xc955e744362ef6b5,
+    0x7daccd3e0f81b959,
+    0xf9c16fe21971ad0c,
+    0xd55388509aa18a38,
+    0xcc3726f40dc2c28d,
+    0xdec581ce6ca460d4,
+    0x51676fd932ff3fb7,
+    0x1947308cf2e1b136,
+    0x956ec0d773bd7acc,
+    0x1b00902832348a17,
+    0x9a688fe1c0a86393,
+    0x097b103bd4686a69,
+    0xc27af7c27f77166f,
+    0x066888c944a92ecb,
+    0x9b09aab6258aa812,
+    0xa55414f7fb50c31e,
+    0x254008983332655e,
+    0xf98c40f3ae3bf407,
+    0x34e04482081079c0,
+    0xd596cb3a46d69fa2,
+    0x59b83f8e8c5820a8,
+    0xc6ab34bcd3ffff7b,
+    0x8964dd4bf5c00792,
+    0xa40b238fe6d117a3,
+    0xf7429a1d92e2a228,
+    0x2910ba4b04c03faf,
+    0x34d926535897936b,
+    0x69b24ca6b12f26d6,
+    0x921014c99c2b0833,
+];
+
 // CRC table for the NVME polynomial (`0xAD93D23594C93659`).
 // Generated by running `./build_table 0`.
 //
 // Essentially, `TABLE_0[m]` is the polynomial remainder in GF(2) when `m`
 // (`1 = x⁷¹, 2 = x⁷⁰, 4 = x⁶⁹, …, 128 = x⁶⁴`) is divided by the NVME poly.
-static TABLE_0: [u64; 256] = [
+pub(crate) static TABLE_0: [u64; 256] = TABLE_0_CONST;
+
+// The same table as a `const`, for use in `update_const`. Indexing a `const`
+// array at runtime may copy the whole array first, so all other lookups go
+// through the `static`.
+const TABLE_0_CONST: [u64; 256] = [
     0x0000000000000000,
     0x7f6ef0c830358979,
     0xfedde190606b12f2,
@@ -340,6 +615,7 @@ static TABLE_0: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x⁷⁹, …, 128 = x⁷²`).
 // Generated by running `./build_table 1`.
+#[cfg(not(feature = "byte-at-a-time"))]
 static TABLE_1: [u64; 256] = [
     0x0000000000000000,
     0x8776a97d73bddf69,
@@ -601,6 +877,7 @@ static TABLE_1: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x⁸⁷, …, 128 = x⁸⁰`).
 // Generated by running `./build_table 2`.
+#[cfg(not(feature = "byte-at-a-time"))]
 static TABLE_2: [u64; 256] = [
     0x0000000000000000,
     0xff6e4e1f4e4038be,
@@ -862,6 +1139,7 @@ static TABLE_2: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x⁹⁵, …, 128 = x⁸⁸`).
 // Generated by running `./build_table 3`.
+#[cfg(not(feature = "byte-at-a-time"))]
 static TABLE_3: [u64; 256] = [
     0x0000000000000000,
     0x8211147cbaf96306,
@@ -1123,6 +1401,7 @@ static TABLE_3: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁰³, …, 128 = x⁹⁶`).
 // Generated by running `./build_table 4`.
+#[cfg(not(any(feature = "slice-by-4", feature = "byte-at-a-time")))]
 static TABLE_4: [u64; 256] = [
     0x0000000000000000,
     0x373d15f784905d1e,
@@ -1384,6 +1663,7 @@ static TABLE_4: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹¹¹, …, 128 = x¹⁰⁴`).
 // Generated by running `./build_table 5`.
+#[cfg(not(any(feature = "slice-by-4", feature = "byte-at-a-time")))]
 static TABLE_5: [u64; 256] = [
     0x0000000000000000,
     0xe9742a79ef04a5d4,
@@ -1645,6 +1925,7 @@ static TABLE_5: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹¹⁹, …, 128 = x¹¹²`).
 // Generated by running `./build_table 6`.
+#[cfg(not(any(feature = "slice-by-4", feature = "byte-at-a-time")))]
 static TABLE_6: [u64; 256] = [
     0x0000000000000000,
     0xfc5d27f6bf353971,
@@ -1906,6 +2187,7 @@ static TABLE_6: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹²⁷, …, 128 = x¹²⁰`).
 // Generated by running `./build_table 7`.
+#[cfg(not(any(feature = "slice-by-4", feature = "byte-at-a-time")))]
 static TABLE_7: [u64; 256] = [
     0x0000000000000000,
     0x21e9761e252621ac,
@@ -2167,6 +2449,11 @@ static TABLE_7: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹³⁵, …, 128 = x¹²⁸`).
 // Generated by running `./build_table 8`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_8: [u64; 256] = [
     0x0000000000000000,
     0x04f28def5347786c,
@@ -2428,6 +2715,11 @@ static TABLE_8: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁴³, …, 128 = x¹³⁶`).
 // Generated by running `./build_table 9`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_9: [u64; 256] = [
     0x0000000000000000,
     0x49e1df807414fdef,
@@ -2689,6 +2981,11 @@ static TABLE_9: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁵¹, …, 128 = x¹⁴⁴`).
 // Generated by running `./build_table 10`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_10: [u64; 256] = [
     0x0000000000000000,
     0x52734ea3e726fc54,
@@ -2950,6 +3247,11 @@ static TABLE_10: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁵⁹, …, 128 = x¹⁵²`).
 // Generated by running `./build_table 11`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_11: [u64; 256] = [
     0x0000000000000000,
     0x668ab3bbc976d29d,
@@ -3211,6 +3513,11 @@ static TABLE_11: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁶⁷, …, 128 = x¹⁶⁰`).
 // Generated by running `./build_table 12`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_12: [u64; 256] = [
     0x0000000000000000,
     0xf2fa1fae5f5c1165,
@@ -3472,6 +3779,11 @@ static TABLE_12: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁷⁵, …, 128 = x¹⁶⁸`).
 // Generated by running `./build_table 13`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_13: [u64; 256] = [
     0x0000000000000000,
     0x9065cb6e6d39918a,
@@ -3733,6 +4045,11 @@ static TABLE_13: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁸³, …, 128 = x¹⁷⁶`).
 // Generated by running `./build_table 14`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_14: [u64; 256] = [
     0x0000000000000000,
     0xc23dfbc6ca591ca3,
@@ -3994,6 +4311,11 @@ static TABLE_14: [u64; 256] = [
 
 // CRC table for the NVME polynomial in the range (`1 = x¹⁹¹, …, 128 = x¹⁸⁴`).
 // Generated by running `./build_table 15`.
+#[cfg(not(any(
+    feature = "slice-by-8",
+    feature = "slice-by-4",
+    feature = "byte-at-a-time"
+)))]
 static TABLE_15: [u64; 256] = [
     0x0000000000000000,
     0xeadc41fd2ba3d420,
@@ -4252,3 +4574,286 @@ static TABLE_15: [u64; 256] = [
     0xf283281474f593a1,
     0x185f69e95f564781,
 ];
+
+// Reverse CRC table for the NVME polynomial, used to run the register backwards.
+// Generated by running `./build_table rev`.
+//
+// `TABLE_REV[t]` is `TABLE_0[i] << 8 | i`, where `i` is the unique index for
+// which the top byte of `TABLE_0[i]` is `t`.
+static TABLE_REV: [u64; 256] = [
+    0x0000000000000000,
+    0x34d926535897936b,
+    0x69b24ca6b12f26d6,
+    0x5d6b6af5e9b8b5bd,
+    0xd364994d625e4dac,
+    0xe7bdbf1e3ac9dec7,
+    0xbad6d5ebd3716b7a,
+    0x8e0ff3b88be6f811,
+    0x921014c99c2b0833,
+    0xa6c9329ac4bc9b58,
+    0xfba2586f2d042ee5,
+    0xcf7b7e3c7593bd8e,
+    0x41748d84fe75459f,
+    0x75adabd7a6e2d6f4,
+    0x28c6c1224f5a6349,
+    0x1c1fe77117cdf022,
+    0x10f90fc060c1830d,
+    0x2420299338561066,
+    0x794b4366d1eea5db,
+    0x4d926535897936b0,
+    0xc39d968d029fcea1,
+    0xf744b0de5a085dca,
+    0xaa2fda2bb3b0e877,
+    0x9ef6fc78eb277b1c,
+    0x82e91b09fcea8b3e,
+    0xb6303d5aa47d1855,
+    0xeb5b57af4dc5ade8,
+    0xdf8271fc15523e83,
+    0x518d82449eb4c692,
+    0x6554a417c62355f9,
+    0x383fcee22f9be044,
+    0x0ce6e8b1770c732f,
+    0x21f21f80c183061a,
+    0x152b39d399149571,
+    0x4840532670ac20cc,
+    0x7c997575283bb3a7,
+    0xf29686cda3dd4bb6,
+    0xc64fa09efb4ad8dd,
+    0x9b24ca6b12f26d60,
+    0xaffdec384a65fe0b,
+    0xb3e20b495da80e29,
+    0x873b2d1a053f9d42,
+    0xda5047efec8728ff,
+    0xee8961bcb410bb94,
+    0x608692043ff64385,
+    0x545fb4576761d0ee,
+    0x0934dea28ed96553,
+    0x3dedf8f1d64ef638,
+    0x310b1040a1428517,
+    0x05d23613f9d5167c,
+    0x58b95ce6106da3c1,
+    0x6c607ab548fa30aa,
+    0xe26f890dc31cc8bb,
+    0xd6b6af5e9b8b5bd0,
+    0x8bddc5ab7233ee6d,
+    0xbf04e3f82aa47d06,
+    0xa31b04893d698d24,
+    0x97c222da65fe1e4f,
+    0xcaa9482f8c46abf2,
+    0xfe706e7cd4d13899,
+    0x707f9dc45f37c088,
+    0x44a6bb9707a053e3,
+    0x19cdd162ee18e65e,
+    0x2d14f731b68f7535,
+    0x43e43f0183060c34,
+    0x773d1952db919f5f,
+    0x2a5673a732292ae2,
+    0x1e8f55f46abeb989,
+    0x9080a64ce1584198,
+    0xa459801fb9cfd2f3,
+    0xf932eaea5077674e,
+    0xcdebccb908e0f425,
+    0xd1f42bc81f2d0407,
+    0xe52d0d9b47ba976c,
+    0xb846676eae0222d1,
+    0x8c9f413df695b1ba,
+    0x0290b2857d7349ab,
+    0x364994d625e4dac0,
+    0x6b22fe23cc5c6f7d,
+    0x5ffbd87094cbfc16,
+    0x531d30c1e3c78f39,
+    0x67c41692bb501c52,
+    0x3aaf7c6752e8a9ef,
+    0x0e765a340a7f3a84,
+    0x8079a98c8199c295,
+    0xb4a08fdfd90e51fe,
+    0xe9cbe52a30b6e443,
+    0xdd12c37968217728,
+    0xc10d24087fec870a,
+    0xf5d4025b277b1461,
+    0xa8bf68aecec3a1dc,
+    0x9c664efd965432b7,
+    0x1269bd451db2caa6,
+    0x26b09b16452559cd,
+    0x7bdbf1e3ac9dec70,
+    0x4f02d7b0f40a7f1b,
+    0x6216208142850a2e,
+    0x56cf06d21a129945,
+    0x0ba46c27f3aa2cf8,
+    0x3f7d4a74ab3dbf93,
+    0xb172b9cc20db4782,
+    0x85ab9f9f784cd4e9,
+    0xd8c0f56a91f46154,
+    0xec19d339c963f23f,
+    0xf0063448deae021d,
+    0xc4df121b86399176,
+    0x99b478ee6f8124cb,
+    0xad6d5ebd3716b7a0,
+    0x2362ad05bcf04fb1,
+    0x17bb8b56e467dcda,
+    0x4ad0e1a30ddf6967,
+    0x7e09c7f05548fa0c,
+    0x72ef2f4122448923,
+    0x463609127ad31a48,
+    0x1b5d63e7936baff5,
+    0x2f8445b4cbfc3c9e,
+    0xa18bb60c401ac48f,
+    0x9552905f188d57e4,
+    0xc839faaaf135e259,
+    0xfce0dcf9a9a27132,
+    0xe0ff3b88be6f8110,
+    0xd4261ddbe6f8127b,
+    0x894d772e0f40a7c6,
+    0xbd94517d57d734ad,
+    0x339ba2c5dc31ccbc,
+    0x0742849684a65fd7,
+    0x5a29ee636d1eea6a,
+    0x6ef0c83035897901,
+    0x87c87e03060c1868,
+    0xb31158505e9b8b03,
+    0xee7a32a5b7233ebe,
+    0xdaa314f6efb4add5,
+    0x54ace74e645255c4,
+    0x6075c11d3cc5c6af,
+    0x3d1eabe8d57d7312,
+    0x09c78dbb8deae079,
+    0x15d86aca9a27105b,
+    0x21014c99c2b08330,
+    0x7c6a266c2b08368d,
+    0x48b3003f739fa5e6,
+    0xc6bcf387f8795df7,
+    0xf265d5d4a0eece9c,
+    0xaf0ebf2149567b21,
+    0x9bd7997211c1e84a,
+    0x973171c366cd9b65,
+    0xa3e857903e5a080e,
+    0xfe833d65d7e2bdb3,
+    0xca5a1b368f752ed8,
+    0x4455e88e0493d6c9,
+    0x708ccedd5c0445a2,
+    0x2de7a428b5bcf01f,
+    0x193e827bed2b6374,
+    0x0521650afae69356,
+    0x31f84359a271003d,
+    0x6c9329ac4bc9b580,
+    0x584a0fff135e26eb,
+    0xd645fc4798b8defa,
+    0xe29cda14c02f4d91,
+    0xbff7b0e12997f82c,
+    0x8b2e96b271006b47,
+    0xa63a6183c78f1e72,
+    0x92e347d09f188d19,
+    0xcf882d2576a038a4,
+    0xfb510b762e37abcf,
+    0x755ef8cea5d153de,
+    0x4187de9dfd46c0b5,
+    0x1cecb46814fe7508,
+    0x2835923b4c69e663,
+    0x342a754a5ba41641,
+    0x00f353190333852a,
+    0x5d9839ecea8b3097,
+    0x69411fbfb21ca3fc,
+    0xe74eec0739fa5bed,
+    0xd397ca54616dc886,
+    0x8efca0a188d57d3b,
+    0xba2586f2d042ee50,
+    0xb6c36e43a74e9d7f,
+    0x821a4810ffd90e14,
+    0xdf7122e51661bba9,
+    0xeba804b64ef628c2,
+    0x65a7f70ec510d0d3,
+    0x517ed15d9d8743b8,
+    0x0c15bba8743ff605,
+    0x38cc9dfb2ca8656e,
+    0x24d37a8a3b65954c,
+    0x100a5cd963f20627,
+    0x4d61362c8a4ab39a,
+    0x79b8107fd2dd20f1,
+    0xf7b7e3c7593bd8e0,
+    0xc36ec59401ac4b8b,
+    0x9e05af61e814fe36,
+    0xaadc8932b0836d5d,
+    0xc42c4102850a145c,
+    0xf0f56751dd9d8737,
+    0xad9e0da43425328a,
+    0x99472bf76cb2a1e1,
+    0x1748d84fe75459f0,
+    0x2391fe1cbfc3ca9b,
+    0x7efa94e9567b7f26,
+    0x4a23b2ba0eecec4d,
+    0x563c55cb19211c6f,
+    0x62e5739841b68f04,
+    0x3f8e196da80e3ab9,
+    0x0b573f3ef099a9d2,
+    0x8558cc867b7f51c3,
+    0xb181ead523e8c2a8,
+    0xecea8020ca507715,
+    0xd833a67392c7e47e,
+    0xd4d54ec2e5cb9751,
+    0xe00c6891bd5c043a,
+    0xbd67026454e4b187,
+    0x89be24370c7322ec,
+    0x07b1d78f8795dafd,
+    0x3368f1dcdf024996,
+    0x6e039b2936bafc2b,
+    0x5adabd7a6e2d6f40,
+    0x46c55a0b79e09f62,
+    0x721c7c5821770c09,
+    0x2f7716adc8cfb9b4,
+    0x1bae30fe90582adf,
+    0x95a1c3461bbed2ce,
+    0xa178e515432941a5,
+    0xfc138fe0aa91f418,
+    0xc8caa9b3f2066773,
+    0xe5de5e8244891246,
+    0xd10778d11c1e812d,
+    0x8c6c1224f5a63490,
+    0xb8b53477ad31a7fb,
+    0x36bac7cf26d75fea,
+    0x0263e19c7e40cc81,
+    0x5f088b6997f8793c,
+    0x6bd1ad3acf6fea57,
+    0x77ce4a4bd8a21a75,
+    0x43176c188035891e,
+    0x1e7c06ed698d3ca3,
+    0x2aa520be311aafc8,
+    0xa4aad306bafc57d9,
+    0x9073f555e26bc4b2,
+    0xcd189fa00bd3710f,
+    0xf9c1b9f35344e264,
+    0xf52751422448914b,
+    0xc1fe77117cdf0220,
+    0x9c951de49567b79d,
+    0xa84c3bb7cdf024f6,
+    0x2643c80f4616dce7,
+    0x129aee5c1e814f8c,
+    0x4ff184a9f739fa31,
+    0x7b28a2faafae695a,
+    0x6737458bb8639978,
+    0x53ee63d8e0f40a13,
+    0x0e85092d094cbfae,
+    0x3a5c2f7e51db2cc5,
+    0xb453dcc6da3dd4d4,
+    0x808afa9582aa47bf,
+    0xdde190606b12f202,
+    0xe938b63333856169,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::CRC_NVME;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    proptest! {
+        // Covers whichever table variant is selected by the features.
+        #[test]
+        fn equivalent_to_crc(bytes in any_with::<Vec<u8>>(size_range(..4096).lift()), offset in 0..16usize) {
+            let crc = crc::Crc::<u64>::new(&CRC_NVME);
+            let bytes = &bytes[offset.min(bytes.len())..];
+            prop_assert_eq!(!update(!0, bytes), crc.checksum(bytes));
+            prop_assert_eq!(update_const(!0, bytes), update(!0, bytes));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Storing the CRC-64/NVME right after the data it protects.
+//!
+//! When the checksum is appended little-endian to the data, running the CRC
+//! register over both always ends in the same value, the residue of the
+//! polynomial, so a reader can verify the whole record in a single pass.
+
+use super::{table, Digest};
+
+/// Appends the little-endian CRC-64/NVME of `buf` to it.
+///
+/// ```rust
+/// use crc64fast_nvme::{append_crc, verify_with_trailer};
+///
+/// let mut record = b"hello world!".to_vec();
+/// append_crc(&mut record);
+/// assert_eq!(record[12..], 0xd9160d1fa8e418e3u64.to_le_bytes());
+/// assert!(verify_with_trailer(&record));
+/// ```
+#[cfg(feature = "std")]
+pub fn append_crc(buf: &mut Vec<u8>) {
+    let mut digest = Digest::new();
+    digest.write(buf);
+    buf.extend_from_slice(&digest.sum64().to_le_bytes());
+}
+
+/// Checks that the last 8 bytes of `data` are the little-endian CRC-64/NVME
+/// of the bytes before them.
+///
+/// Returns `false` if `data` is shorter than 8 bytes.
+pub fn verify_with_trailer(data: &[u8]) -> bool {
+    if data.len() < 8 {
+        return false;
+    }
+    let mut digest = Digest::new();
+    digest.write(data);
+    digest.is_residue_valid()
+}
+
+impl Digest {
+    /// Returns whether the data written so far ends with the little-endian
+    /// CRC-64/NVME of the data before it.
+    ///
+    /// This lets streaming readers verify a record with a trailing checksum
+    /// without splitting off its last 8 bytes.
+    pub fn is_residue_valid(&self) -> bool {
+        self.amount >= 8 && self.state == table::RESIDUE
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use proptest::collection::size_range;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_short_data() {
+        assert!(!verify_with_trailer(b""));
+        assert!(!verify_with_trailer(b"1234567"));
+
+        let mut record = Vec::new();
+        append_crc(&mut record);
+        assert_eq!(record, [0; 8]);
+        assert!(verify_with_trailer(&record));
+    }
+
+    proptest! {
+        #[test]
+        fn append_and_verify(bytes in any_with::<Vec<u8>>(size_range(..65536).lift()), index in any::<usize>()) {
+            let mut record = bytes.clone();
+            append_crc(&mut record);
+            prop_assert_eq!(record.len(), bytes.len() + 8);
+            prop_assert!(verify_with_trailer(&record));
+
+            // streaming in two parts
+            let (left, right) = record.split_at(index % record.len());
+            let mut digest = Digest::new();
+            digest.write(left);
+            digest.write(right);
+            prop_assert!(digest.is_residue_valid());
+
+            // any corruption is detected
+            let index = index % record.len();
+            record[index] ^= 1;
+            prop_assert!(!verify_with_trailer(&record));
+        }
+    }
+}